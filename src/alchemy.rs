@@ -0,0 +1,242 @@
+// Alchemy: potions brewed from aspects and Vis over time, drunk for timed buffs
+use crate::commands::GameCommand;
+use crate::{styled_button, Clicker};
+use eframe::egui;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+// potion id -> recipe (preserve JSON order)
+pub type PotionBook = IndexMap<String, PotionRecipe>;
+
+#[derive(Deserialize, Clone)]
+pub struct PotionRecipe {
+    pub name: String,
+    pub description: String,
+    // Research node that must be unlocked before the potion can be brewed
+    pub requires: Option<String>,
+    pub cost: IndexMap<String, u32>,
+    // Seconds spent in the cauldron
+    pub brew_time: f32,
+    pub buff: BuffSpec,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BuffSpec {
+    pub effect: BuffEffect,
+    pub amount: f32,
+    // Seconds the buff lasts once drunk
+    pub duration: f32,
+}
+
+// Multiplier effects stack by product, bonus effects by sum (percentage points)
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BuffEffect {
+    VisPerClick,
+    CrystalChance,
+    CrystalYield,
+    CraftChance,
+    TransmuteRate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Brewing {
+    pub potion: String,
+    pub remaining: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActiveBuff {
+    pub potion: String,
+    pub effect: BuffEffect,
+    pub amount: f32,
+    pub remaining: f32,
+}
+
+pub fn format_countdown(secs: f32) -> String {
+    let secs = secs.max(0.0).ceil() as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+impl Clicker {
+    pub fn potion_available(&self, id: &str) -> bool {
+        if !self.unlocks.alchemy { return false; }
+        match self.potions.get(id).and_then(|p| p.requires.as_ref()) {
+            Some(node) => self.unlocked_nodes.contains(node),
+            None => self.potions.contains_key(id),
+        }
+    }
+
+    pub fn start_brew(&mut self, id: &str) -> bool {
+        if self.brewing.is_some() || !self.potion_available(id) { return false; }
+        let Some(recipe) = self.potions.get(id).cloned() else { return false; };
+        if !self.can_afford_cost(&recipe.cost) { return false; }
+        self.spend_cost(&recipe.cost);
+        self.brewing = Some(Brewing { potion: id.to_string(), remaining: recipe.brew_time });
+        true
+    }
+
+    pub fn drink_potion(&mut self, id: &str) -> bool {
+        let Some(recipe) = self.potions.get(id) else { return false; };
+        let buff = recipe.buff.clone();
+        let Some(stock) = self.potion_stock.get_mut(id) else { return false; };
+        if !crate::safe_subtract(stock, 1) { return false; }
+        // Drinking the same potion again refreshes its timer instead of stacking
+        if let Some(active) = self.active_buffs.iter_mut().find(|b| b.potion == id) {
+            active.remaining = active.remaining.max(buff.duration);
+        } else {
+            self.active_buffs.push(ActiveBuff {
+                potion: id.to_string(),
+                effect: buff.effect,
+                amount: buff.amount,
+                remaining: buff.duration,
+            });
+        }
+        true
+    }
+
+    // Advance the cauldron and buff timers by `dt` seconds
+    pub fn tick_alchemy(&mut self, dt: f32) {
+        if let Some(brew) = &mut self.brewing {
+            brew.remaining -= dt;
+            if brew.remaining <= 0.0 {
+                *self.potion_stock.entry(brew.potion.clone()).or_insert(0) += 1;
+                self.brewing = None;
+            }
+        }
+        for buff in &mut self.active_buffs {
+            buff.remaining -= dt;
+        }
+        self.active_buffs.retain(|b| b.remaining > 0.0);
+    }
+
+    pub fn buff_multiplier(&self, effect: BuffEffect) -> f32 {
+        self.active_buffs.iter().filter(|b| b.effect == effect).map(|b| b.amount).product()
+    }
+
+    pub fn buff_bonus(&self, effect: BuffEffect) -> u32 {
+        self.active_buffs.iter().filter(|b| b.effect == effect).map(|b| b.amount as u32).sum()
+    }
+
+    pub fn show_buff_strip(&self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            for buff in &self.active_buffs {
+                let name = self.potions.get(&buff.potion).map(|p| p.name.as_str()).unwrap_or(&buff.potion);
+                ui.label(
+                    egui::RichText::new(format!("{} {}", name, format_countdown(buff.remaining)))
+                        .color(egui::Color32::from_rgb(200, 170, 255)),
+                );
+                ui.separator();
+            }
+        });
+    }
+
+    pub fn show_alchemy(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Alchemy").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Brew potions from aspects and Vis, then drink them for timed buffs.").color(egui::Color32::WHITE));
+        ui.separator();
+
+        match &self.brewing {
+            Some(brew) => {
+                let name = self.potions.get(&brew.potion).map(|p| p.name.clone()).unwrap_or(brew.potion.clone());
+                let total = self.potions.get(&brew.potion).map(|p| p.brew_time).unwrap_or(1.0).max(0.001);
+                ui.label(egui::RichText::new(format!("Brewing {} ({} left)", name, format_countdown(brew.remaining))).color(egui::Color32::WHITE));
+                ui.add(egui::ProgressBar::new(1.0 - brew.remaining / total).desired_width(300.0));
+            }
+            None => {
+                ui.label(egui::RichText::new("The cauldron is empty.").color(egui::Color32::LIGHT_GRAY));
+            }
+        }
+        ui.separator();
+
+        let ids: Vec<String> = self.potions.keys().cloned().collect();
+        for id in ids {
+            if !self.potion_available(&id) { continue; }
+            let recipe = self.potions[&id].clone();
+            let stock = self.potion_stock.get(&id).copied().unwrap_or(0);
+            ui.group(|ui| {
+                ui.label(egui::RichText::new(&recipe.name).strong().color(egui::Color32::LIGHT_BLUE));
+                ui.label(egui::RichText::new(&recipe.description).color(egui::Color32::WHITE));
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Cost: {}  |  Brew: {}  |  Lasts: {}",
+                        self.cost_text(&recipe.cost),
+                        format_countdown(recipe.brew_time),
+                        format_countdown(recipe.buff.duration)
+                    ))
+                    .color(egui::Color32::LIGHT_GRAY),
+                );
+                ui.horizontal(|ui| {
+                    let can_brew = self.brewing.is_none() && self.can_afford_cost(&recipe.cost);
+                    if ui.add_enabled(can_brew, styled_button("Brew")).clicked() {
+                        self.apply(GameCommand::Brew { potion: id.clone() });
+                    }
+                    if ui.add_enabled(stock > 0, styled_button(&format!("Drink ({})", stock))).clicked() {
+                        self.apply(GameCommand::Drink { potion: id.clone() });
+                    }
+                });
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    fn stocked(potions: &[&str]) -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        for id in potions {
+            app.potion_stock.insert(id.to_string(), 2);
+        }
+        app
+    }
+
+    #[test]
+    fn drinking_again_refreshes_instead_of_stacking() {
+        let mut app = stocked(&["vis_tonic"]);
+        assert!(app.drink_potion("vis_tonic"));
+        app.tick_alchemy(100.0);
+        assert!(app.drink_potion("vis_tonic"));
+        assert_eq!(app.active_buffs.len(), 1);
+        assert_eq!(app.active_buffs[0].remaining, 180.0);
+        assert_eq!(app.buff_multiplier(BuffEffect::VisPerClick), 2.0);
+        // Out of stock now
+        assert!(!app.drink_potion("vis_tonic"));
+    }
+
+    #[test]
+    fn different_potions_stack_and_expire() {
+        let mut app = stocked(&["vis_tonic", "draught_of_plenty", "steady_hand_elixir"]);
+        for id in ["vis_tonic", "draught_of_plenty", "steady_hand_elixir"] {
+            assert!(app.drink_potion(id));
+        }
+        assert_eq!(app.buff_multiplier(BuffEffect::VisPerClick), 2.0);
+        assert_eq!(app.buff_multiplier(BuffEffect::CrystalChance), 2.0);
+        assert_eq!(app.buff_bonus(BuffEffect::CraftChance), 15);
+        // The tonic runs out first; the others keep going
+        app.tick_alchemy(180.0);
+        assert_eq!(app.buff_multiplier(BuffEffect::VisPerClick), 1.0);
+        assert_eq!(app.buff_bonus(BuffEffect::CraftChance), 15);
+    }
+
+    #[test]
+    fn brewing_fills_the_stock_when_done() {
+        let mut app = test_clicker(test_save(1));
+        app.unlocks.alchemy = true;
+        assert!(!app.start_brew("vis_tonic"), "needs its research first");
+        app.unlocked_nodes.insert("apprentice_bench".to_string());
+        app.vis = 30;
+        app.crystals.insert("aqua".to_string(), 4);
+        app.crystals.insert("potentia".to_string(), 2);
+        assert!(app.start_brew("vis_tonic"));
+        assert!(!app.start_brew("vis_tonic"), "the cauldron is busy");
+        app.tick_alchemy(19.0);
+        assert!(app.brewing.is_some());
+        app.tick_alchemy(1.0);
+        assert!(app.brewing.is_none());
+        assert_eq!(app.potion_stock.get("vis_tonic"), Some(&1));
+        assert_eq!((app.vis, app.crystals["aqua"]), (0, 0));
+    }
+}
@@ -0,0 +1,189 @@
+// Aspect registry: display names, tiers, colours, lore and icons for every
+// aspect, loaded from aspects.json. UI code asks here instead of using raw ids.
+use crate::Clicker;
+use eframe::egui;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+// Shown in place of the name of an aspect the player hasn't discovered
+pub const UNDISCOVERED_NAME: &str = "???";
+
+// aspect id -> metadata (preserve JSON order; inventory lists follow it)
+pub type AspectRegistry = IndexMap<String, AspectInfo>;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AspectInfo {
+    pub name: String,
+    // "primal", or the recipes.json category the aspect is crafted in
+    pub tier: String,
+    // "#rrggbb"
+    pub color: String,
+    pub description: String,
+    // File name under assets/aspects (or a mod's aspects folder)
+    pub icon: String,
+    #[serde(default)]
+    pub primal: bool,
+}
+
+impl AspectInfo {
+    pub fn color32(&self) -> Option<egui::Color32> {
+        parse_color(&self.color)
+    }
+}
+
+pub fn parse_color(hex: &str) -> Option<egui::Color32> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 { return None; }
+    let v = u32::from_str_radix(hex, 16).ok()?;
+    Some(egui::Color32::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8))
+}
+
+impl Clicker {
+    pub fn aspect(&self, id: &str) -> Option<&AspectInfo> {
+        self.aspects.get(id)
+    }
+
+    // Display name; unknown ids (and "Vis") are shown as-is, undiscovered aspects as "???"
+    pub fn aspect_name(&self, id: &str) -> String {
+        match self.aspect(id) {
+            Some(_) if !self.aspect_discovered(id) => UNDISCOVERED_NAME.to_string(),
+            Some(a) => a.name.clone(),
+            None => id.to_string(),
+        }
+    }
+
+    pub fn aspect_color(&self, id: &str) -> egui::Color32 {
+        match self.aspect(id) {
+            Some(_) if !self.aspect_discovered(id) => egui::Color32::GRAY,
+            Some(a) => a.color32().unwrap_or(egui::Color32::WHITE),
+            None => egui::Color32::WHITE,
+        }
+    }
+
+    pub fn aspect_discovered(&self, id: &str) -> bool {
+        self.discovered_aspects.contains(id)
+    }
+
+    // Icons of undiscovered aspects are drawn as black silhouettes
+    pub fn icon_tint(&self, id: &str) -> egui::Color32 {
+        if self.aspect(id).is_some() && !self.aspect_discovered(id) { egui::Color32::BLACK } else { egui::Color32::WHITE }
+    }
+
+    // Every way of gaining an aspect goes through here so first acquisitions are noticed
+    pub fn add_aspect(&mut self, id: &str, amount: u32) {
+        if amount == 0 { return; }
+        *self.crystals.entry(id.to_string()).or_insert(0) += amount;
+        self.discovered_aspects.insert(id.to_string());
+    }
+
+    // Apply the "aspect:" reveals of nodes already researched, which covers the
+    // default save's starting nodes and reveals added to the data after the fact
+    pub fn reveal_researched_aspects(&mut self) {
+        let revealed: Vec<String> = self
+            .research
+            .values()
+            .flatten()
+            .filter(|n| self.unlocked_nodes.contains(&n.id))
+            .flat_map(|n| n.unlocks.iter().flatten())
+            .filter_map(|u| u.strip_prefix("aspect:"))
+            .map(str::to_string)
+            .collect();
+        self.discovered_aspects.extend(revealed);
+    }
+
+    // Aspects gathering can turn up
+    pub fn primal_aspects(&self) -> Vec<String> {
+        self.aspects.iter().filter(|(_, a)| a.primal).map(|(id, _)| id.clone()).collect()
+    }
+
+    // "Aer x5, Ordo x5"
+    pub fn cost_text(&self, cost: &IndexMap<String, u32>) -> String {
+        cost.iter().map(|(k, v)| format!("{} x{}", self.aspect_name(k), v)).collect::<Vec<_>>().join(", ")
+    }
+
+    pub fn aspect_text(&self, id: &str) -> egui::RichText {
+        egui::RichText::new(self.aspect_name(id)).color(self.aspect_color(id))
+    }
+
+    // "Aer: 12" in the aspect's colour
+    pub fn aspect_count_text(&self, id: &str, amount: u32) -> egui::RichText {
+        egui::RichText::new(format!("{}: {}", self.aspect_name(id), amount)).color(self.aspect_color(id))
+    }
+
+    // Tooltip body for an aspect: name, tier and lore
+    pub fn show_aspect_info(&self, ui: &mut egui::Ui, id: &str) {
+        let Some(info) = self.aspect(id) else {
+            ui.label(id);
+            return;
+        };
+        if !self.aspect_discovered(id) {
+            ui.label(egui::RichText::new("Undiscovered aspect").italics().color(egui::Color32::GRAY));
+            return;
+        }
+        ui.label(self.aspect_text(id).strong());
+        ui.label(egui::RichText::new(format!("{} aspect", info.tier)).color(egui::Color32::LIGHT_GRAY));
+        ui.label(&info.description);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    #[test]
+    fn registry_supplies_names_colours_and_primals() {
+        let mut app = test_clicker(test_save(1));
+        app.discovered_aspects.insert("lux".to_string());
+        assert_eq!(app.aspect_name("lux"), "Lux");
+        assert_eq!(app.aspect_color("lux"), egui::Color32::from_rgb(0xff, 0xf6, 0x63));
+        // Ids outside the registry pass through untouched
+        assert_eq!(app.aspect_name("Vis"), "Vis");
+        assert_eq!(app.aspect_color("Vis"), egui::Color32::WHITE);
+        let primals = app.primal_aspects();
+        assert_eq!(primals.len(), 6);
+        assert!(primals.iter().all(|id| app.aspect(id).is_some_and(|a| a.tier == "primal")));
+    }
+
+    #[test]
+    fn undiscovered_aspects_are_masked_until_gained() {
+        let mut app = test_clicker(test_save(1));
+        app.discovered_aspects.clear();
+        assert_eq!(app.aspect_name("lux"), UNDISCOVERED_NAME);
+        assert_eq!(app.aspect_color("lux"), egui::Color32::GRAY);
+        assert_eq!(app.icon_tint("lux"), egui::Color32::BLACK);
+        assert_eq!(app.cost_text(&IndexMap::from([("lux".to_string(), 2)])), "??? x2");
+
+        app.add_aspect("lux", 0);
+        assert!(!app.aspect_discovered("lux"), "gaining nothing discovers nothing");
+        app.add_aspect("lux", 1);
+        assert_eq!(app.aspect_name("lux"), "Lux");
+        assert_eq!(app.icon_tint("lux"), egui::Color32::WHITE);
+    }
+
+    #[test]
+    fn research_reveals_aspects_and_discovery_is_saved() {
+        let mut app = test_clicker(test_save(1));
+        app.discovered_aspects.clear();
+        app.unlocked_nodes.insert("primal_lore".to_string());
+        app.reveal_researched_aspects();
+        for primal in app.primal_aspects() {
+            assert!(app.aspect_discovered(&primal), "{} still hidden", primal);
+        }
+        assert!(!app.aspect_discovered("lux"));
+
+        // Spent again, so only the saved discovery can keep it known
+        app.add_aspect("lux", 1);
+        app.crystals.insert("lux".to_string(), 0);
+        let restored = test_clicker(crate::build_save(&app));
+        assert!(restored.aspect_discovered("lux"));
+    }
+
+    #[test]
+    fn colours_must_be_six_hex_digits() {
+        assert_eq!(parse_color("#0a0b0c"), Some(egui::Color32::from_rgb(10, 11, 12)));
+        assert_eq!(parse_color("0a0b0c"), None);
+        assert_eq!(parse_color("#fff"), None);
+        assert_eq!(parse_color("#gggggg"), None);
+    }
+}
@@ -0,0 +1,47 @@
+// Headless balance simulator. Plays from a fresh save and writes the timeline
+// as both JSON and CSV.
+//
+//   clicker-sim [--strategy greedy|target:<node>|click-only] [--clicks-per-sec N]
+//               [--duration SECS] [--seed N] [--out BASE]
+//               [--out-json PATH] [--out-csv PATH]
+//
+// `--out BASE` writes BASE.json and BASE.csv (default: clicker-sim); the
+// per-format flags override either path, and `-` prints that format to stdout.
+use anyhow::{Context, Result};
+use clicker_game::sim::{simulate, SimConfig, Strategy};
+
+fn write_output(path: &str, output: &str) -> Result<()> {
+    if path == "-" {
+        print!("{}", output);
+        Ok(())
+    } else {
+        std::fs::write(path, output).with_context(|| format!("writing {}", path))
+    }
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let flag_value = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+
+    let strategy = Strategy::parse(&flag_value("--strategy").unwrap_or_else(|| "greedy".to_string()))?;
+    let clicks_per_sec: f32 = match flag_value("--clicks-per-sec") {
+        Some(v) => v.parse().context("--clicks-per-sec expects a number")?,
+        None => 5.0,
+    };
+    let duration: f32 = match flag_value("--duration") {
+        Some(v) => v.parse().context("--duration expects seconds")?,
+        None => 4.0 * 3600.0,
+    };
+    let seed: u64 = match flag_value("--seed") {
+        Some(v) => v.parse().context("--seed expects an unsigned integer")?,
+        None => 0,
+    };
+
+    let report = simulate(&SimConfig { strategy, clicks_per_sec, duration, seed })?;
+    let base = flag_value("--out").unwrap_or_else(|| "clicker-sim".to_string());
+    let json_path = flag_value("--out-json").unwrap_or_else(|| format!("{}.json", base));
+    let csv_path = flag_value("--out-csv").unwrap_or_else(|| format!("{}.csv", base));
+    write_output(&json_path, &report.to_json()?)?;
+    write_output(&csv_path, &report.to_csv()?)?;
+    Ok(())
+}
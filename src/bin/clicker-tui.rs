@@ -0,0 +1,7 @@
+// Terminal build of the game; same save file and flags as the main binary
+fn main() {
+    if let Err(e) = clicker_game::run_frontend(clicker_game::Frontend::Terminal) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
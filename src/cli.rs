@@ -0,0 +1,113 @@
+// Launch options shared by the game binaries, plus the no-window modes they select
+use crate::commands::GameCommand;
+use crate::{build_save, save_game, Clicker};
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::time::Instant;
+
+pub const USAGE: &str = "\
+Usage: clicker-game [options]
+
+  --save <path>          save file to load and write (default: in the user data directory)
+  --portable             keep the save and settings next to the executable
+  --data-dir <path>      load the game data JSON files from this directory
+  --seed <n>             restart the RNG stream from a fixed seed
+  --headless             run without a window, reading JSON commands from stdin
+  --tui                  play in the terminal instead of a window
+  --reset                start over from the default save
+  --dev                  reload data files and icons when they change on disk
+  --validate-data        check the game data for broken references and exit
+  --layout-research      print research.json with automatically laid out coordinates and exit
+  --export-save <path>   write the current save to <path> and exit
+  --import-save <path>   replace the current save with <path>, then launch
+  --windowed <WxH>       open a WxH window instead of a maximized one
+  --record <path>        record every command of this session for replay
+  --replay <path>        replay a recorded session headlessly and print the final state
+  --help                 show this message";
+
+#[derive(Default, Debug, Clone)]
+pub struct LaunchOptions {
+    pub save: Option<PathBuf>,
+    pub portable: bool,
+    pub data_dir: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub headless: bool,
+    pub tui: bool,
+    pub reset: bool,
+    pub validate_data: bool,
+    pub layout_research: bool,
+    pub dev: bool,
+    pub export_save: Option<PathBuf>,
+    pub import_save: Option<PathBuf>,
+    pub windowed: Option<[f32; 2]>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl LaunchOptions {
+    // Parse everything after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut opts = LaunchOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| anyhow!("{} expects a value", name));
+            match arg.as_str() {
+                "--save" => opts.save = Some(value("--save")?.into()),
+                "--portable" => opts.portable = true,
+                "--data-dir" => opts.data_dir = Some(value("--data-dir")?.into()),
+                "--seed" => {
+                    let seed = value("--seed")?;
+                    opts.seed = Some(seed.parse().map_err(|_| anyhow!("--seed expects an unsigned integer"))?);
+                }
+                "--headless" => opts.headless = true,
+                "--tui" => opts.tui = true,
+                "--reset" => opts.reset = true,
+                "--validate-data" => opts.validate_data = true,
+                "--layout-research" => opts.layout_research = true,
+                "--dev" => opts.dev = true,
+                "--export-save" => opts.export_save = Some(value("--export-save")?.into()),
+                "--import-save" => opts.import_save = Some(value("--import-save")?.into()),
+                "--windowed" => opts.windowed = Some(parse_size(&value("--windowed")?)?),
+                "--record" => opts.record = Some(value("--record")?.into()),
+                "--replay" => opts.replay = Some(value("--replay")?.into()),
+                "--help" | "-h" => opts.help = true,
+                other => bail!("unknown option '{}'\n\n{}", other, USAGE),
+            }
+        }
+        if opts.headless && opts.tui {
+            bail!("--headless and --tui can't be combined");
+        }
+        Ok(opts)
+    }
+}
+
+// "1280x720" -> [1280.0, 720.0]
+pub fn parse_size(s: &str) -> Result<[f32; 2]> {
+    let parsed = s.split_once(['x', 'X']).and_then(|(w, h)| Some([w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?]));
+    match parsed {
+        Some([w, h]) if w > 0 && h > 0 => Ok([w as f32, h as f32]),
+        _ => bail!("--windowed expects WIDTHxHEIGHT, e.g. 1280x720"),
+    }
+}
+
+// Headless mode: one JSON `GameCommand` per stdin line, applied as it arrives with
+// the game clock following real time. On EOF the game is saved and its state printed.
+pub fn run_headless(mut app: Clicker) -> Result<()> {
+    let mut last = Instant::now();
+    for (n, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let now = Instant::now();
+        app.advance(now.duration_since(last).as_secs_f32());
+        last = now;
+        if line.trim().is_empty() { continue; }
+        let cmd: GameCommand = serde_json::from_str(&line).with_context(|| format!("stdin line {}", n + 1))?;
+        app.apply(cmd);
+    }
+    app.advance(last.elapsed().as_secs_f32());
+    save_game(&app)?;
+    app.flush_recording();
+    println!("{}", serde_json::to_string_pretty(&build_save(&app))?);
+    Ok(())
+}
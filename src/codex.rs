@@ -0,0 +1,213 @@
+// Codex tab: an encyclopedia of every aspect with its recipe, where it is used and
+// how many the player has crafted. Aspects never obtained show as silhouettes.
+use crate::Clicker;
+use eframe::egui;
+use indexmap::IndexMap;
+
+const TILE_SIZE: egui::Vec2 = egui::vec2(96.0, 64.0);
+
+// Where an aspect is spent
+pub struct Usage {
+    // (category, item) recipes that take it as a component
+    pub recipes: Vec<(String, String)>,
+    // (tab, node name) research nodes that cost it
+    pub research: Vec<(String, String)>,
+}
+
+impl Clicker {
+    // Registry aspects in order, then any recipe items the registry doesn't know
+    pub fn codex_entries(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.aspects.keys().cloned().collect();
+        for item in self.recipes.crystals.values().flat_map(|items| items.keys()) {
+            if !ids.contains(item) { ids.push(item.clone()); }
+        }
+        ids
+    }
+
+    // (category, components) of the recipe that makes `id`
+    pub fn made_from(&self, id: &str) -> Option<(String, IndexMap<String, u32>)> {
+        self.recipes
+            .crystals
+            .iter()
+            .find_map(|(category, items)| items.get(id).map(|cost| (category.clone(), cost.clone())))
+    }
+
+    pub fn used_in(&self, id: &str) -> Usage {
+        let recipes = self
+            .recipes
+            .crystals
+            .iter()
+            .flat_map(|(category, items)| items.iter().map(move |(item, cost)| (category, item, cost)))
+            .filter(|(_, _, cost)| cost.contains_key(id))
+            .map(|(category, item, _)| (category.clone(), item.clone()))
+            .collect();
+        let research = self
+            .research
+            .iter()
+            .flat_map(|(tab, nodes)| nodes.iter().map(move |n| (tab, n)))
+            .filter(|(_, n)| n.cost.contains_key(id))
+            .map(|(tab, n)| (tab.clone(), n.name.clone()))
+            .collect();
+        Usage { recipes, research }
+    }
+
+    fn codex_name(&self, id: &str, suffix: &str) -> egui::RichText {
+        egui::RichText::new(format!("{}{}", self.aspect_name(id), suffix)).color(self.aspect_color(id))
+    }
+
+    fn codex_icon(&mut self, ui: &mut egui::Ui, id: &str, size: f32) {
+        let tint = self.icon_tint(id);
+        match self.get_crystal_icon(ui.ctx(), id).map(|t| t.id()) {
+            Some(tex) => { ui.add(egui::Image::new((tex, egui::vec2(size, size))).tint(tint)); }
+            None => { ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover()); }
+        }
+    }
+
+    pub fn show_codex(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Codex").color(egui::Color32::WHITE));
+        let entries = self.codex_entries();
+        let found = entries.iter().filter(|id| self.aspect_discovered(id)).count();
+        ui.label(egui::RichText::new(format!("{} of {} aspects discovered", found, entries.len())).color(egui::Color32::LIGHT_GRAY));
+        ui.separator();
+
+        let selected = self.codex_selected.clone().or_else(|| entries.first().cloned());
+        ui.horizontal_top(|ui| {
+            ui.vertical(|ui| {
+                ui.set_width(TILE_SIZE.x * 4.0 + 24.0);
+                egui::ScrollArea::vertical().id_salt("codex_list").show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for id in &entries {
+                            let active = selected.as_deref() == Some(id.as_str());
+                            let resp = ui
+                                .allocate_ui(TILE_SIZE, |ui| {
+                                    ui.vertical_centered(|ui| {
+                                        self.codex_icon(ui, id, 32.0);
+                                        ui.label(self.codex_name(id, "").size(12.0));
+                                    });
+                                })
+                                .response
+                                .interact(egui::Sense::click());
+                            if active {
+                                ui.painter().rect_stroke(resp.rect, egui::Rounding::same(4), egui::Stroke::new(1.0, egui::Color32::GOLD), egui::StrokeKind::Inside);
+                            }
+                            if resp.clicked() {
+                                self.codex_selected = Some(id.clone());
+                            }
+                        }
+                    });
+                });
+            });
+            ui.separator();
+            ui.vertical(|ui| {
+                if let Some(id) = &selected {
+                    self.show_codex_entry(ui, id);
+                }
+            });
+        });
+    }
+
+    fn show_codex_entry(&mut self, ui: &mut egui::Ui, id: &str) {
+        ui.horizontal(|ui| {
+            self.codex_icon(ui, id, 64.0);
+            ui.vertical(|ui| {
+                ui.label(self.codex_name(id, "").size(22.0).strong());
+                if let Some(info) = self.aspect(id).filter(|_| self.aspect_discovered(id)) {
+                    ui.label(egui::RichText::new(format!("{} aspect", info.tier)).color(egui::Color32::LIGHT_GRAY));
+                }
+            });
+        });
+        if !self.aspect_discovered(id) {
+            ui.label(egui::RichText::new("Undiscovered. Obtain or research this aspect to learn more about it.").italics().color(egui::Color32::GRAY));
+            return;
+        }
+        if let Some(info) = self.aspect(id) {
+            ui.label(egui::RichText::new(&info.description).color(egui::Color32::WHITE));
+        }
+        let owned = self.crystals.get(id).copied().unwrap_or(0);
+        let crafted = self.crafted.get(id).copied().unwrap_or(0);
+        ui.label(egui::RichText::new(format!("Owned: {}    Crafted (lifetime): {}", owned, crafted)).color(egui::Color32::LIGHT_GRAY));
+        ui.separator();
+
+        ui.label(egui::RichText::new("Made from").strong().color(egui::Color32::LIGHT_BLUE));
+        let primal = self.aspect(id).is_some_and(|a| a.primal);
+        match self.made_from(id) {
+            Some((_, components)) => {
+                ui.horizontal_wrapped(|ui| {
+                    for (component, amount) in &components {
+                        self.codex_link(ui, component, &format!(" x{}", amount));
+                    }
+                });
+            }
+            None if primal => { ui.label(egui::RichText::new("Primal: found by gathering").color(egui::Color32::LIGHT_GRAY)); }
+            None => { ui.label(egui::RichText::new("No known recipe").color(egui::Color32::LIGHT_GRAY)); }
+        }
+
+        let usage = self.used_in(id);
+        ui.label(egui::RichText::new("Used in").strong().color(egui::Color32::LIGHT_BLUE));
+        if usage.recipes.is_empty() && usage.research.is_empty() {
+            ui.label(egui::RichText::new("Nothing yet").color(egui::Color32::LIGHT_GRAY));
+        }
+        if !usage.recipes.is_empty() {
+            ui.horizontal_wrapped(|ui| {
+                for (_, item) in &usage.recipes {
+                    self.codex_link(ui, item, "");
+                }
+            });
+        }
+        for (tab, node) in &usage.research {
+            ui.label(egui::RichText::new(format!("Research: {} ({})", node, tab)).color(egui::Color32::LIGHT_GRAY));
+        }
+    }
+
+    // Small icon + name that opens another codex entry
+    fn codex_link(&mut self, ui: &mut egui::Ui, id: &str, suffix: &str) {
+        self.codex_icon(ui, id, 18.0);
+        if ui.add(egui::Label::new(self.codex_name(id, suffix)).sense(egui::Sense::click())).clicked() {
+            self.codex_selected = Some(id.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    #[test]
+    fn entries_follow_the_registry_then_stray_recipes() {
+        let mut app = test_clicker(test_save(1));
+        let registered = app.aspects.len();
+        app.recipes.crystals["secondary"].insert("oddity".to_string(), IndexMap::from([("aer".to_string(), 1)]));
+        let entries = app.codex_entries();
+        assert_eq!(entries.len(), registered + 1);
+        assert_eq!(entries[..registered], app.aspects.keys().cloned().collect::<Vec<_>>()[..]);
+        assert_eq!(entries.last().map(String::as_str), Some("oddity"));
+    }
+
+    #[test]
+    fn recipes_are_looked_up_both_ways() {
+        let app = test_clicker(test_save(1));
+        let (category, cost) = app.made_from("lux").unwrap();
+        assert_eq!(category, "secondary");
+        assert_eq!(cost, IndexMap::from([("aer".to_string(), 5), ("ignis".to_string(), 5)]));
+        assert!(app.made_from("aer").is_none());
+
+        let lux = app.used_in("lux");
+        assert!(lux.recipes.contains(&("tertiary".to_string(), "tenebrae".to_string())));
+        assert!(lux.recipes.iter().all(|(_, item)| app.made_from(item).unwrap().1.contains_key("lux")));
+        let aer = app.used_in("aer");
+        assert!(aer.recipes.contains(&("secondary".to_string(), "lux".to_string())));
+        assert!(aer.research.contains(&("Crystallurgy".to_string(), "Echo Resonance".to_string())));
+    }
+
+    #[test]
+    fn crafting_counts_toward_the_lifetime_total() {
+        let mut app = test_clicker(test_save(1));
+        app.crystals.insert("aer".to_string(), 5);
+        app.crystals.insert("ignis".to_string(), 5);
+        app.equipment_craft_bonus = (100, 0);
+        app.craft("secondary", "lux");
+        assert_eq!(app.crafted.get("lux"), Some(&1));
+        assert_eq!(test_clicker(crate::build_save(&app)).crafted.get("lux"), Some(&1));
+    }
+}
@@ -0,0 +1,135 @@
+// Game commands: every player action that changes game state goes through
+// `Clicker::apply`, so a session can be recorded and replayed exactly.
+use crate::{safe_subtract, Clicker, OverflowMode};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+// The auto-clicker never fires faster than this
+const MIN_AUTO_CLICK_INTERVAL: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upgrade {
+    VisCapacity,
+    CrystalClickAmount,
+    AutoClickInterval,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 3] = [Upgrade::VisCapacity, Upgrade::CrystalClickAmount, Upgrade::AutoClickInterval];
+
+    // Key in upgrades.json
+    pub fn id(self) -> &'static str {
+        match self {
+            Upgrade::VisCapacity => "vis_capacity",
+            Upgrade::CrystalClickAmount => "crystal_click_amount",
+            Upgrade::AutoClickInterval => "auto_click_interval",
+        }
+    }
+}
+
+// upgrade id -> tuning (preserve JSON order)
+pub type UpgradeBook = IndexMap<String, UpgradeSpec>;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UpgradeSpec {
+    pub name: String,
+    // Vis price per purchase
+    pub cost: u32,
+    // Vis cap / crystals per find added, or seconds taken off the auto-click interval
+    pub amount: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameCommand {
+    Gather,
+    Craft { category: String, name: String },
+    Transmute { item: String },
+    Unlock { node: String },
+    QueueResearch { node: String },
+    DequeueResearch { node: String },
+    BuyUpgrade { upgrade: Upgrade },
+    SetOverflowMode { mode: OverflowMode },
+    ReleaseVisBuffer,
+    Brew { potion: String },
+    Drink { potion: String },
+    CrucibleThrow { aspect: String, amount: u32 },
+    CrucibleEmpty,
+    BurnPraemunio,
+    RiftClick { index: usize },
+}
+
+impl Clicker {
+    pub fn apply(&mut self, cmd: GameCommand) {
+        if let Some(rec) = &mut self.recorder {
+            rec.record(self.sim_tick, &cmd);
+        }
+        match cmd {
+            GameCommand::Gather => self.gather(),
+            GameCommand::Craft { category, name } => self.craft(&category, &name),
+            GameCommand::Transmute { item } => { let _ = self.transmute(&item); }
+            GameCommand::Unlock { node } => {
+                let result = self.unlock_node(&node);
+                self.research_message = self.unlock_message(&node, result);
+            }
+            GameCommand::QueueResearch { node } => {
+                self.research_message = match self.queue_research(&node) {
+                    Ok(queued) => Ok(self.queued_message(&queued)),
+                    Err(err) => self.unlock_message(&node, Err(err)),
+                };
+                // Start right away when nothing else is running
+                self.tick_research(0.0);
+            }
+            GameCommand::DequeueResearch { node } => {
+                let removed = self.dequeue_research(&node);
+                let names: Vec<String> = removed.iter().map(|id| self.research_node(id).map_or(id.clone(), |n| n.name.clone())).collect();
+                self.research_message = Ok(format!("Removed {} from the queue", names.join(", ")));
+            }
+            GameCommand::BuyUpgrade { upgrade } => { self.buy_upgrade(upgrade); }
+            GameCommand::SetOverflowMode { mode } => self.overflow_mode = mode,
+            GameCommand::ReleaseVisBuffer => self.release_vis_buffer(),
+            GameCommand::Brew { potion } => { self.start_brew(&potion); }
+            GameCommand::Drink { potion } => { self.drink_potion(&potion); }
+            GameCommand::CrucibleThrow { aspect, amount } => { self.crucible_throw(&aspect, amount); }
+            GameCommand::CrucibleEmpty => { self.crucible_empty(); }
+            GameCommand::BurnPraemunio => { self.burn_praemunio(); }
+            GameCommand::RiftClick { index } => self.rift_clicked(index),
+        }
+    }
+
+    fn upgrade_spec(&self, upgrade: Upgrade) -> Option<&UpgradeSpec> {
+        self.upgrades.get(upgrade.id())
+    }
+
+    pub fn upgrade_label(&self, upgrade: Upgrade) -> String {
+        let Some(spec) = self.upgrade_spec(upgrade) else { return upgrade.id().to_string(); };
+        match upgrade {
+            Upgrade::AutoClickInterval => format!("{} (-{}s)", spec.name, spec.amount),
+            _ => format!("{} (+{}) ({} Vis)", spec.name, spec.amount, spec.cost),
+        }
+    }
+
+    pub fn upgrade_available(&self, upgrade: Upgrade) -> bool {
+        let Some(spec) = self.upgrade_spec(upgrade) else { return false; };
+        match upgrade {
+            Upgrade::AutoClickInterval => self.unlocks.autoCliking && self.vis >= spec.cost,
+            _ => self.vis >= spec.cost,
+        }
+    }
+
+    pub fn buy_upgrade(&mut self, upgrade: Upgrade) -> bool {
+        let Some(spec) = self.upgrade_spec(upgrade).cloned() else { return false; };
+        if upgrade == Upgrade::AutoClickInterval
+            && (!self.unlocks.autoCliking || self.autoClickInterval - spec.amount < MIN_AUTO_CLICK_INTERVAL)
+        {
+            return false;
+        }
+        if !safe_subtract(&mut self.vis, spec.cost) { return false; }
+        match upgrade {
+            Upgrade::VisCapacity => self.maxVis += spec.amount as u32,
+            Upgrade::CrystalClickAmount => self.crystalClickAmount += spec.amount as u32,
+            Upgrade::AutoClickInterval => self.autoClickInterval -= spec.amount,
+        }
+        true
+    }
+}
@@ -0,0 +1,214 @@
+// Crucible: crystals dissolve into a shared essentia pool, and any recipe whose
+// aspect costs are covered by the pool precipitates out on its own.
+use crate::commands::GameCommand;
+use crate::flux::FLUX_PER_DECAYED_ESSENTIA;
+use crate::{styled_button, Clicker};
+use eframe::egui;
+use indexmap::IndexMap;
+
+// Seconds between decay steps of leftover essentia
+pub const CRUCIBLE_DECAY_INTERVAL: f32 = 15.0;
+// Recent formations kept for the crucible log
+const CRUCIBLE_LOG_LEN: usize = 6;
+
+impl Clicker {
+    // Dissolve `amount` crystals of `aspect` into the pool, then let recipes form
+    pub fn crucible_throw(&mut self, aspect: &str, amount: u32) -> bool {
+        let Some(have) = self.crystals.get_mut(aspect) else { return false; };
+        let amount = amount.min(*have);
+        if amount == 0 { return false; }
+        *have -= amount;
+        *self.crucible.entry(aspect.to_string()).or_insert(0) += amount;
+        self.crucible_react();
+        true
+    }
+
+    // Match recipes against the pool, highest unlocked tier first so rarer aspects win
+    pub fn crucible_react(&mut self) {
+        loop {
+            let formed = self
+                .recipes
+                .crystals
+                .iter()
+                .rev()
+                .filter(|(cat, _)| self.category_unlocked(cat))
+                .flat_map(|(_, items)| items.iter())
+                .find(|(name, costs)| {
+                    // A recipe that costs nothing would form forever
+                    costs.values().any(|&amt| amt > 0)
+                        && !costs.contains_key(name.as_str())
+                        && costs.iter().all(|(k, amt)| self.crucible.get(k).copied().unwrap_or(0) >= *amt)
+                })
+                .map(|(name, costs)| (name.clone(), costs.clone()));
+            let Some((name, costs)) = formed else { break; };
+            for (k, amt) in costs.iter() {
+                if let Some(v) = self.crucible.get_mut(k) { *v -= amt; }
+            }
+            self.crucible.retain(|_, v| *v > 0);
+            self.add_aspect(&name, 1);
+            *self.crafted.entry(name.clone()).or_insert(0) += 1;
+            self.crucible_log.push(format!("{} precipitated", self.aspect_name(&name)));
+            if self.crucible_log.len() > CRUCIBLE_LOG_LEN {
+                self.crucible_log.remove(0);
+            }
+        }
+    }
+
+    // Leftover essentia slowly breaks down: each step, every non-perditio essentia loses
+    // one unit to perditio
+    pub fn tick_crucible(&mut self, dt: f32) {
+        if self.crucible.is_empty() {
+            self.crucible_decay_timer = 0.0;
+            return;
+        }
+        self.crucible_decay_timer += dt;
+        while self.crucible_decay_timer >= CRUCIBLE_DECAY_INTERVAL {
+            self.crucible_decay_timer -= CRUCIBLE_DECAY_INTERVAL;
+            let mut decayed = 0;
+            for (k, v) in self.crucible.iter_mut() {
+                if k != "perditio" && *v > 0 {
+                    *v -= 1;
+                    decayed += 1;
+                }
+            }
+            self.crucible.retain(|_, v| *v > 0);
+            if decayed > 0 {
+                *self.crucible.entry("perditio".to_string()).or_insert(0) += decayed;
+                self.add_flux(decayed as f32 * FLUX_PER_DECAYED_ESSENTIA);
+                self.crucible_react();
+            }
+        }
+    }
+
+    pub fn show_crucible(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Crucible").color(egui::Color32::WHITE));
+        ui.label(
+            egui::RichText::new("Throw crystals in to dissolve them. Any recipe the essentia can cover forms on its own; leftovers decay into perditio.")
+                .color(egui::Color32::WHITE),
+        );
+        ui.separator();
+
+        ui.label(egui::RichText::new("Essentia pool").strong().color(egui::Color32::LIGHT_BLUE));
+        if self.crucible.is_empty() {
+            ui.label(egui::RichText::new("The crucible is empty.").color(egui::Color32::LIGHT_GRAY));
+        } else {
+            let pool: Vec<(String, u32)> = self.crucible.iter().map(|(k, v)| (k.clone(), *v)).collect();
+            ui.horizontal_wrapped(|ui| {
+                for (aspect, amount) in pool {
+                    if let Some(tex) = self.get_crystal_icon(ui.ctx(), &aspect) {
+                        ui.add(egui::Image::new((tex.id(), egui::vec2(18.0, 18.0))));
+                    }
+                    ui.label(self.aspect_count_text(&aspect, amount)).on_hover_ui(|ui| self.show_aspect_info(ui, &aspect));
+                    ui.add_space(8.0);
+                }
+            });
+            let next = (CRUCIBLE_DECAY_INTERVAL - self.crucible_decay_timer).max(0.0);
+            ui.label(egui::RichText::new(format!("Next decay in {:.0}s", next)).color(egui::Color32::LIGHT_GRAY));
+            if ui.add(styled_button("Empty crucible")).clicked() {
+                self.apply(GameCommand::CrucibleEmpty);
+            }
+        }
+        for line in &self.crucible_log {
+            ui.label(egui::RichText::new(line).color(egui::Color32::from_rgb(120, 220, 120)));
+        }
+        ui.separator();
+
+        ui.label(egui::RichText::new("Throw in").strong().color(egui::Color32::LIGHT_BLUE));
+        let owned: Vec<(String, u32)> = self
+            .crystals
+            .iter()
+            .filter(|(_, v)| **v > 0)
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        ui.horizontal_wrapped(|ui| {
+            for (aspect, amount) in owned {
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        if let Some(tex) = self.get_crystal_icon(ui.ctx(), &aspect) {
+                            ui.add(egui::Image::new((tex.id(), egui::vec2(18.0, 18.0))));
+                        }
+                        ui.label(egui::RichText::new(format!("{} ({})", self.aspect_name(&aspect), amount)).color(self.aspect_color(&aspect)))
+                            .on_hover_ui(|ui| self.show_aspect_info(ui, &aspect));
+                        if ui.button("+1").clicked() { self.apply(GameCommand::CrucibleThrow { aspect: aspect.clone(), amount: 1 }); }
+                        if ui.button("+5").clicked() { self.apply(GameCommand::CrucibleThrow { aspect: aspect.clone(), amount: 5 }); }
+                    });
+                });
+            }
+        });
+    }
+
+    // Pour out the pool; the essentia is lost and fouls the air as flux
+    pub fn crucible_empty(&mut self) -> IndexMap<String, u32> {
+        self.crucible_decay_timer = 0.0;
+        let spilled = std::mem::take(&mut self.crucible);
+        self.add_flux(spilled.values().sum::<u32>() as f32 * FLUX_PER_DECAYED_ESSENTIA);
+        spilled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    fn costs(pairs: &[(&str, u32)]) -> IndexMap<String, u32> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    // "lux" (2 aer) is secondary, "motus" (2 aer + 1 ignis) tertiary, and
+    // "vacous" (3 perditio) forms only from decayed essentia
+    fn app() -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        app.recipes.crystals = IndexMap::from([
+            ("secondary".to_string(), IndexMap::from([("lux".to_string(), costs(&[("aer", 2)])), ("vacous".to_string(), costs(&[("perditio", 3)]))])),
+            ("tertiary".to_string(), IndexMap::from([("motus".to_string(), costs(&[("aer", 2), ("ignis", 1)]))])),
+        ]);
+        app.unlocks.secondary_crystals = true;
+        app.unlocks.tertiary_crystals = true;
+        for aspect in ["aer", "ignis"] {
+            app.crystals.insert(aspect.to_string(), 10);
+        }
+        app.flux = 0.0;
+        app
+    }
+
+    fn held(app: &Clicker, aspect: &str) -> u32 {
+        app.crystals.get(aspect).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn higher_tier_recipes_form_first() {
+        let mut app = app();
+        assert!(app.crucible_throw("ignis", 1));
+        assert!(app.crucible_throw("aer", 2));
+        assert_eq!((held(&app, "motus"), held(&app, "lux")), (1, 0));
+        assert!(app.crucible.is_empty());
+
+        // With the tertiary tier locked the same pool makes the secondary one
+        app.unlocks.tertiary_crystals = false;
+        app.crucible_throw("ignis", 1);
+        app.crucible_throw("aer", 2);
+        assert_eq!((held(&app, "motus"), held(&app, "lux")), (1, 1));
+        assert_eq!(app.crucible.get("ignis"), Some(&1));
+    }
+
+    #[test]
+    fn leftovers_decay_into_perditio() {
+        let mut app = app();
+        app.crucible_throw("aer", 1);
+        app.crucible_throw("ignis", 2);
+        app.tick_crucible(CRUCIBLE_DECAY_INTERVAL - 1.0);
+        assert_eq!(app.crucible.get("perditio"), None);
+
+        // One step: aer and ignis each lose a unit to perditio
+        app.tick_crucible(1.0);
+        assert_eq!(app.crucible.get("aer"), None);
+        assert_eq!((app.crucible.get("ignis"), app.crucible.get("perditio")), (Some(&1), Some(&2)));
+        assert!((app.flux - 2.0 * FLUX_PER_DECAYED_ESSENTIA).abs() < 1e-4);
+
+        // The next step brings perditio to 3, which precipitates on its own
+        app.tick_crucible(CRUCIBLE_DECAY_INTERVAL);
+        assert_eq!(held(&app, "vacous"), 1);
+        assert!(app.crucible.is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+// World events: data-driven timed happenings rolled from the game RNG,
+// announced through the notification area.
+use crate::commands::GameCommand;
+use crate::Clicker;
+use eframe::egui;
+use rand::Rng;
+use serde::Deserialize;
+
+// Seconds between scheduled events (uniform in this range)
+const EVENT_MIN_DELAY: f32 = 90.0;
+const EVENT_MAX_DELAY: f32 = 240.0;
+// Seconds a notification stays on screen
+const NOTIFICATION_SECS: f32 = 6.0;
+
+#[derive(Deserialize, Clone)]
+pub struct WorldEvent {
+    pub id: String,
+    pub name: String,
+    pub announcement: String,
+    // Relative chance among eligible events
+    pub weight: u32,
+    // Seconds the effect lasts; 0 for instant effects
+    pub duration: f32,
+    #[serde(default)]
+    pub condition: EventCondition,
+    pub effect: EventEffect,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EventCondition {
+    pub requires_node: Option<String>,
+    pub min_flux: Option<f32>,
+    pub max_flux: Option<f32>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventEffect {
+    // Multiplies Vis per click while active
+    VisMultiplier { amount: f32 },
+    // Instantly grants `amount` of one aspect picked from the list
+    AspectRain { aspects: Vec<String>, amount: u32 },
+    // A floating target that grants `amount` of a listed aspect per click while active
+    Rift { aspects: Vec<String>, amount: u32 },
+}
+
+impl EventEffect {
+    // Aspects the effect can hand out
+    pub fn aspects(&self) -> &[String] {
+        match self {
+            EventEffect::VisMultiplier { .. } => &[],
+            EventEffect::AspectRain { aspects, .. } | EventEffect::Rift { aspects, .. } => aspects,
+        }
+    }
+}
+
+pub struct ActiveEvent {
+    pub event: WorldEvent,
+    pub remaining: f32,
+    // Rift target position as a fraction of the screen
+    pub target: egui::Vec2,
+}
+
+pub struct EventScheduler {
+    pub next_in: f32,
+    pub active: Vec<ActiveEvent>,
+}
+
+impl EventScheduler {
+    // Weighted pick among eligible candidates
+    pub fn pick<'a>(rng: &mut impl Rng, candidates: &[&'a WorldEvent]) -> Option<&'a WorldEvent> {
+        let total: u32 = candidates.iter().map(|e| e.weight).sum();
+        if total == 0 { return None; }
+        let mut roll = rng.gen_range(0..total);
+        for e in candidates {
+            if roll < e.weight { return Some(e); }
+            roll -= e.weight;
+        }
+        None
+    }
+
+    fn random_target(rng: &mut impl Rng) -> egui::Vec2 {
+        egui::vec2(rng.gen_range(0.2..0.7), rng.gen_range(0.25..0.75))
+    }
+}
+
+impl Default for EventScheduler {
+    fn default() -> Self {
+        Self { next_in: EVENT_MIN_DELAY, active: Vec::new() }
+    }
+}
+
+pub struct Notification {
+    pub text: String,
+    pub remaining: f32,
+}
+
+impl Clicker {
+    pub fn notify(&mut self, text: impl Into<String>) {
+        self.notifications.push(Notification { text: text.into(), remaining: NOTIFICATION_SECS });
+    }
+
+    fn event_eligible(&self, event: &WorldEvent) -> bool {
+        let c = &event.condition;
+        if c.requires_node.as_ref().is_some_and(|node| !self.unlocked_nodes.contains(node)) { return false; }
+        if c.min_flux.is_some_and(|min| self.flux < min) { return false; }
+        if c.max_flux.is_some_and(|max| self.flux > max) { return false; }
+        // Don't stack the same event on itself
+        !self.events.active.iter().any(|a| a.event.id == event.id)
+    }
+
+    // Product of Vis multipliers from active events
+    pub fn event_vis_multiplier(&self) -> f32 {
+        self.events
+            .active
+            .iter()
+            .map(|a| match a.event.effect {
+                EventEffect::VisMultiplier { amount } => amount,
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    pub fn tick_events(&mut self, dt: f32) {
+        for n in &mut self.notifications {
+            n.remaining -= dt;
+        }
+        self.notifications.retain(|n| n.remaining > 0.0);
+
+        let mut ended: Vec<String> = Vec::new();
+        for a in &mut self.events.active {
+            a.remaining -= dt;
+            if a.remaining <= 0.0 { ended.push(a.event.name.clone()); }
+        }
+        self.events.active.retain(|a| a.remaining > 0.0);
+        for name in ended {
+            self.notify(format!("{} has ended.", name));
+        }
+
+        self.events.next_in -= dt;
+        if self.events.next_in > 0.0 { return; }
+        self.events.next_in = self.rng.gen_range(EVENT_MIN_DELAY..EVENT_MAX_DELAY);
+
+        let events = std::mem::take(&mut self.world_events);
+        let candidates: Vec<&WorldEvent> = events.iter().filter(|e| self.event_eligible(e)).collect();
+        let picked = EventScheduler::pick(&mut self.rng, &candidates).cloned();
+        self.world_events = events;
+        if let Some(event) = picked {
+            self.start_event(event);
+        }
+    }
+
+    pub fn start_event(&mut self, event: WorldEvent) {
+        self.notify(event.announcement.clone());
+        match &event.effect {
+            EventEffect::AspectRain { aspects, amount } => {
+                if aspects.is_empty() { return; }
+                let aspect = aspects[self.rng.gen_range(0..aspects.len())].clone();
+                self.add_aspect(&aspect, *amount);
+                self.notify(format!("+{} {}", amount, self.aspect_name(&aspect)));
+            }
+            EventEffect::VisMultiplier { .. } | EventEffect::Rift { .. } => {
+                if event.duration <= 0.0 { return; }
+                let target = EventScheduler::random_target(&mut self.rng);
+                let remaining = event.duration;
+                self.events.active.push(ActiveEvent { event, remaining, target });
+            }
+        }
+    }
+
+    // Click on an open rift: grant a rare aspect and move the target
+    pub fn rift_clicked(&mut self, idx: usize) {
+        if idx >= self.events.active.len() { return; }
+        let EventEffect::Rift { aspects, amount } = self.events.active[idx].event.effect.clone() else { return; };
+        if aspects.is_empty() { return; }
+        let aspect = aspects[self.rng.gen_range(0..aspects.len())].clone();
+        self.add_aspect(&aspect, amount);
+        self.events.active[idx].target = EventScheduler::random_target(&mut self.rng);
+        self.notify(format!("The rift yields {} {}", amount, self.aspect_name(&aspect)));
+    }
+
+    pub fn show_event_strip(&self, ui: &mut egui::Ui) {
+        for a in &self.events.active {
+            ui.label(
+                egui::RichText::new(format!("{} {:.0}s", a.event.name, a.remaining.max(0.0).ceil()))
+                    .color(egui::Color32::from_rgb(255, 210, 120)),
+            );
+            ui.separator();
+        }
+    }
+
+    // Notification area plus any floating rift targets
+    pub fn show_event_overlay(&mut self, ctx: &egui::Context) {
+        if !self.notifications.is_empty() {
+            egui::Area::new(egui::Id::new("notifications"))
+                .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 70.0))
+                .interactable(false)
+                .show(ctx, |ui| {
+                    for n in &self.notifications {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(egui::RichText::new(&n.text).color(egui::Color32::WHITE));
+                        });
+                    }
+                });
+        }
+
+        let screen = ctx.screen_rect();
+        let mut clicked: Option<usize> = None;
+        for (idx, a) in self.events.active.iter().enumerate() {
+            if !matches!(a.event.effect, EventEffect::Rift { .. }) { continue; }
+            let pos = screen.min + egui::vec2(screen.width() * a.target.x, screen.height() * a.target.y);
+            egui::Area::new(egui::Id::new(("rift_target", idx)))
+                .fixed_pos(pos)
+                .show(ctx, |ui| {
+                    let label = egui::RichText::new(format!("Rift ({:.0}s)", a.remaining)).color(egui::Color32::WHITE);
+                    let button = egui::Button::new(label)
+                        .fill(egui::Color32::from_rgb(110, 40, 160))
+                        .min_size([90.0, 40.0].into());
+                    if ui.add(button).clicked() { clicked = Some(idx); }
+                });
+        }
+        if let Some(index) = clicked {
+            self.apply(GameCommand::RiftClick { index });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::GameRng;
+    use crate::{test_clicker, test_save};
+
+    fn event(id: &str, weight: u32, condition: EventCondition) -> WorldEvent {
+        WorldEvent {
+            id: id.to_string(),
+            name: id.to_string(),
+            announcement: String::new(),
+            weight,
+            duration: 10.0,
+            condition,
+            effect: EventEffect::VisMultiplier { amount: 2.0 },
+        }
+    }
+
+    #[test]
+    fn pick_follows_the_weights() {
+        let light = event("light", 1, EventCondition::default());
+        let heavy = event("heavy", 3, EventCondition::default());
+        let never = event("never", 0, EventCondition::default());
+        let mut rng = GameRng::new(5);
+        let mut heavy_picks = 0;
+        for _ in 0..4000 {
+            let picked = EventScheduler::pick(&mut rng, &[&light, &never, &heavy]).unwrap();
+            assert_ne!(picked.id, "never");
+            if picked.id == "heavy" { heavy_picks += 1; }
+        }
+        assert!((2800..3200).contains(&heavy_picks), "heavy picked {} of 4000", heavy_picks);
+        assert!(EventScheduler::pick(&mut rng, &[&never]).is_none());
+        assert!(EventScheduler::pick(&mut rng, &[]).is_none());
+    }
+
+    #[test]
+    fn conditions_and_running_events_limit_eligibility() {
+        let mut app = test_clicker(test_save(1));
+        app.flux = 30.0;
+        let gated = event("gated", 1, EventCondition { requires_node: Some("gate".to_string()), ..Default::default() });
+        let calm = event("calm", 1, EventCondition { max_flux: Some(20.0), ..Default::default() });
+        let stormy = event("stormy", 1, EventCondition { min_flux: Some(25.0), ..Default::default() });
+        assert_eq!([&gated, &calm, &stormy].map(|e| app.event_eligible(e)), [false, false, true]);
+
+        app.unlocked_nodes.insert("gate".to_string());
+        app.flux = 10.0;
+        assert_eq!([&gated, &calm, &stormy].map(|e| app.event_eligible(e)), [true, true, false]);
+
+        app.events.active.push(ActiveEvent { event: calm.clone(), remaining: 5.0, target: egui::Vec2::ZERO });
+        assert!(!app.event_eligible(&calm));
+    }
+}
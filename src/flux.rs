@@ -0,0 +1,179 @@
+// Flux: magical pollution from failed crafts, wasted Vis and heavy automation.
+// It bleeds off slowly; above the thresholds below it periodically lashes out.
+use crate::commands::GameCommand;
+use crate::Clicker;
+use eframe::egui;
+use rand::Rng;
+
+pub const FLUX_MAX: f32 = 100.0;
+// Flux gained per source
+pub const FLUX_CRAFT_FAILURE: f32 = 3.0;
+pub const FLUX_PER_LOST_VIS: f32 = 0.05;
+pub const FLUX_PER_DECAYED_ESSENTIA: f32 = 0.5;
+pub const FLUX_PER_AUTOCLICK: f32 = 0.25;
+// Passive decay per second; praemunio wards multiply it
+const FLUX_DECAY_PER_SEC: f32 = 0.02;
+const FLUX_WARD_DECAY_MULT: f32 = 4.0;
+// Burning one praemunio crystal purges this much flux (needs wards)
+const FLUX_PER_PRAEMUNIO: f32 = 10.0;
+
+// Seconds between rolls for a flux event, and the (threshold, % chance per roll) tiers
+const FLUX_EVENT_INTERVAL: f32 = 20.0;
+const FLUX_EVENT_TIERS: [(f32, u32); 3] = [(25.0, 15), (50.0, 35), (75.0, 60)];
+// Temporary maxVis reduction caused by a flux event
+const FLUX_CAP_PENALTY_PERCENT: u32 = 25;
+const FLUX_CAP_PENALTY_SECS: f32 = 60.0;
+
+impl Clicker {
+    pub fn add_flux(&mut self, amount: f32) {
+        self.flux = (self.flux + amount).clamp(0.0, FLUX_MAX);
+    }
+
+    // Vis cap after any active flux penalty
+    pub fn effective_max_vis(&self) -> u32 {
+        self.maxVis.saturating_sub(self.flux_vis_penalty)
+    }
+
+    pub fn flux_decay_rate(&self) -> f32 {
+        if self.unlocks.flux_wards { FLUX_DECAY_PER_SEC * FLUX_WARD_DECAY_MULT } else { FLUX_DECAY_PER_SEC }
+    }
+
+    // Spend one praemunio crystal to purge flux
+    pub fn burn_praemunio(&mut self) -> bool {
+        if !self.unlocks.flux_wards || self.flux <= 0.0 { return false; }
+        let burned = self.crystals.get_mut("praemunio").map(|v| crate::safe_subtract(v, 1)).unwrap_or(false);
+        if burned { self.add_flux(-FLUX_PER_PRAEMUNIO); }
+        burned
+    }
+
+    pub fn tick_flux(&mut self, dt: f32) {
+        self.add_flux(-self.flux_decay_rate() * dt);
+
+        if self.flux_penalty_timer > 0.0 {
+            self.flux_penalty_timer -= dt;
+            if self.flux_penalty_timer <= 0.0 {
+                self.flux_penalty_timer = 0.0;
+                self.flux_vis_penalty = 0;
+                self.flux_message = "The Vis cap has recovered.".to_string();
+            }
+        }
+
+        let Some(chance) = self.flux_event_chance() else {
+            self.flux_event_timer = 0.0;
+            return;
+        };
+        self.flux_event_timer += dt;
+        if self.flux_event_timer < FLUX_EVENT_INTERVAL { return; }
+        self.flux_event_timer = 0.0;
+
+        if self.rng.gen_range(0..100) >= chance { return; }
+        if self.rng.gen_bool(0.5) {
+            self.flux_event_lose_crystals();
+        } else {
+            self.flux_event_shrink_cap();
+        }
+    }
+
+    // % chance per roll of a flux event at the current flux, if it is above the first tier
+    fn flux_event_chance(&self) -> Option<u32> {
+        FLUX_EVENT_TIERS.iter().rev().find(|(threshold, _)| self.flux >= *threshold).map(|(_, c)| *c)
+    }
+
+    // A random owned aspect loses a quarter of its stock (at least one)
+    fn flux_event_lose_crystals(&mut self) {
+        let owned: Vec<String> = self.crystals.iter().filter(|(_, v)| **v > 0).map(|(k, _)| k.clone()).collect();
+        if owned.is_empty() { return self.flux_event_shrink_cap(); }
+        let aspect = &owned[self.rng.gen_range(0..owned.len())];
+        let Some(have) = self.crystals.get_mut(aspect) else { return; };
+        let lost = (*have / 4).max(1);
+        *have -= lost;
+        self.flux_message = format!("Flux tore through your stores: lost {} {}.", lost, self.aspect_name(aspect));
+    }
+
+    fn flux_event_shrink_cap(&mut self) {
+        self.flux_vis_penalty = self.maxVis * FLUX_CAP_PENALTY_PERCENT / 100;
+        self.flux_penalty_timer = FLUX_CAP_PENALTY_SECS;
+        self.vis = self.vis.min(self.effective_max_vis());
+        self.flux_message = format!("Flux warps your reservoir: Vis cap reduced by {} for {:.0}s.", self.flux_vis_penalty, FLUX_CAP_PENALTY_SECS);
+    }
+
+    pub fn show_flux_meter(&mut self, ui: &mut egui::Ui) {
+        let frac = self.flux / FLUX_MAX;
+        let color = match FLUX_EVENT_TIERS.iter().filter(|(t, _)| self.flux >= *t).count() {
+            0 => egui::Color32::from_rgb(120, 90, 160),
+            1 => egui::Color32::from_rgb(170, 80, 170),
+            2 => egui::Color32::from_rgb(200, 60, 120),
+            _ => egui::Color32::from_rgb(230, 40, 60),
+        };
+        ui.label(egui::RichText::new("Flux").color(egui::Color32::WHITE));
+        ui.add(egui::ProgressBar::new(frac).fill(color).text(format!("{:.0}/{:.0}", self.flux, FLUX_MAX)));
+        if self.flux_vis_penalty > 0 {
+            ui.label(
+                egui::RichText::new(format!("Vis cap -{} ({:.0}s)", self.flux_vis_penalty, self.flux_penalty_timer))
+                    .color(egui::Color32::LIGHT_RED),
+            );
+        }
+        if self.unlocks.flux_wards {
+            let praemunio = self.crystals.get("praemunio").copied().unwrap_or(0);
+            if ui.add_enabled(praemunio > 0 && self.flux > 0.0, egui::Button::new(format!("Burn {} ({})", self.aspect_name("praemunio"), praemunio))).clicked() {
+                self.apply(GameCommand::BurnPraemunio);
+            }
+        }
+        if !self.flux_message.is_empty() {
+            ui.label(egui::RichText::new(&self.flux_message).color(egui::Color32::from_rgb(220, 160, 220)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    fn app(flux: f32) -> Clicker {
+        let mut app = test_clicker(test_save(3));
+        app.flux = flux;
+        app
+    }
+
+    #[test]
+    fn event_chance_follows_the_tiers() {
+        let chances: Vec<Option<u32>> = [0.0, 24.9, 25.0, 49.9, 50.0, 75.0, FLUX_MAX].iter().map(|&f| app(f).flux_event_chance()).collect();
+        assert_eq!(chances, vec![None, None, Some(15), Some(15), Some(35), Some(60), Some(60)]);
+    }
+
+    #[test]
+    fn low_flux_never_lashes_out() {
+        let mut app = app(24.0);
+        for _ in 0..50 {
+            app.tick_flux(FLUX_EVENT_INTERVAL);
+        }
+        assert_eq!(app.flux_event_timer, 0.0);
+        assert!(app.flux_message.is_empty());
+        assert_eq!(app.flux_vis_penalty, 0);
+    }
+
+    #[test]
+    fn high_flux_rolls_once_per_interval() {
+        let mut app = app(FLUX_MAX);
+        app.tick_flux(FLUX_EVENT_INTERVAL - 1.0);
+        assert!(app.flux_message.is_empty(), "no roll before the interval");
+        // With the seeded stream, some roll at 60% lands within a few intervals
+        for _ in 0..10 {
+            app.tick_flux(FLUX_EVENT_INTERVAL);
+            if !app.flux_message.is_empty() { break; }
+        }
+        assert!(app.flux_message.starts_with("Flux"));
+    }
+
+    #[test]
+    fn cap_penalty_wears_off() {
+        let mut app = app(0.0);
+        app.vis = app.maxVis;
+        app.flux_event_shrink_cap();
+        assert_eq!(app.flux_vis_penalty, app.maxVis * FLUX_CAP_PENALTY_PERCENT / 100);
+        assert_eq!(app.vis, app.effective_max_vis());
+        app.tick_flux(FLUX_CAP_PENALTY_SECS);
+        assert_eq!(app.effective_max_vis(), app.maxVis);
+    }
+}
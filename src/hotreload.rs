@@ -0,0 +1,125 @@
+// Dev mode (`--dev`): polls the data directory, mods and aspect icons for changes
+// and reloads them into the running game. Parse errors are shown in an overlay
+// and the previous data stays in use until the files are fixed.
+use crate::{Clicker, GameData};
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Seconds between scans of the watched folders
+const POLL_INTERVAL: f32 = 1.0;
+pub const ICON_DIR: &str = "assets/aspects";
+
+pub struct DevWatcher {
+    data_dirs: Vec<PathBuf>,
+    icon_dir: PathBuf,
+    mtimes: HashMap<PathBuf, SystemTime>,
+    timer: f32,
+    // Edited icons from ICON_DIR, re-applied under any mod icons after a reload
+    icons: HashMap<String, PathBuf>,
+}
+
+#[derive(Default)]
+struct Changes {
+    data: bool,
+    // Aspect names whose icon file changed
+    icons: Vec<(String, PathBuf)>,
+}
+
+fn scan(dir: &Path, out: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return; };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            scan(&path, out);
+        } else if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            out.insert(path, modified);
+        }
+    }
+}
+
+impl DevWatcher {
+    pub fn new(data_dirs: Vec<PathBuf>) -> Self {
+        let mut watcher = Self { data_dirs, icon_dir: PathBuf::from(ICON_DIR), mtimes: HashMap::new(), timer: 0.0, icons: HashMap::new() };
+        watcher.mtimes = watcher.snapshot();
+        watcher
+    }
+
+    fn snapshot(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut mtimes = HashMap::new();
+        for dir in self.data_dirs.iter().chain(std::iter::once(&self.icon_dir)) {
+            scan(dir, &mut mtimes);
+        }
+        mtimes
+    }
+
+    fn poll(&mut self, dt: f32) -> Option<Changes> {
+        self.timer += dt;
+        if self.timer < POLL_INTERVAL { return None; }
+        self.timer = 0.0;
+
+        let now = self.snapshot();
+        let mut changes = Changes::default();
+        let changed = now
+            .iter()
+            .filter(|(path, t)| self.mtimes.get(*path) != Some(t))
+            .map(|(path, _)| path.clone())
+            .chain(self.mtimes.keys().filter(|p| !now.contains_key(*p)).cloned());
+        for path in changed {
+            let is_png = path.extension().is_some_and(|x| x == "png");
+            match path.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) if is_png => {
+                    // A new mod icon only takes effect once the mod is re-read
+                    changes.data |= !path.starts_with(&self.icon_dir);
+                    changes.icons.push((stem.to_string(), path.clone()));
+                }
+                _ => changes.data |= path.extension().is_some_and(|x| x == "json"),
+            }
+        }
+        self.mtimes = now;
+        (changes.data || !changes.icons.is_empty()).then_some(changes)
+    }
+}
+
+impl Clicker {
+    pub fn poll_dev_reload(&mut self, dt: f32) {
+        let Some(watcher) = self.dev.as_mut() else { return; };
+        let Some(changes) = watcher.poll(dt) else { return; };
+        for (aspect, path) in &changes.icons {
+            // Icons from the dev folder win over the embedded copies until restart
+            if path.starts_with(&watcher.icon_dir) && path.exists() {
+                watcher.icons.insert(aspect.clone(), path.clone());
+                self.icon_overrides.entry(aspect.clone()).or_insert(path.clone());
+            }
+            self.textures.remove(aspect);
+        }
+        if changes.data {
+            self.dev_errors = match GameData::load(&self.data_source) {
+                Ok(data) => {
+                    let problems = data.problems();
+                    self.install_data(data);
+                    for (aspect, path) in self.dev.iter().flat_map(|w| w.icons.iter()) {
+                        self.icon_overrides.entry(aspect.clone()).or_insert(path.clone());
+                    }
+                    problems
+                }
+                Err(e) => vec![format!("reload failed, keeping the previous data: {:#}", e)],
+            };
+        }
+    }
+
+    pub fn show_dev_overlay(&self, ctx: &egui::Context) {
+        if self.dev.is_none() || self.dev_errors.is_empty() { return; }
+        egui::Area::new(egui::Id::new("dev_errors"))
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(10.0, -10.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).fill(egui::Color32::from_rgb(60, 20, 20)).show(ui, |ui| {
+                    ui.label(egui::RichText::new("Game data problems").strong().color(egui::Color32::WHITE));
+                    for e in &self.dev_errors {
+                        ui.label(egui::RichText::new(e).monospace().color(egui::Color32::LIGHT_RED));
+                    }
+                });
+            });
+    }
+}
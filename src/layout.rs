@@ -0,0 +1,273 @@
+// Automatic research layout: nodes without `x`/`y` are placed by a layered DAG
+// layout of their tab. Layers follow prerequisite depth from left to right, and
+// the order within each layer is chosen to reduce edge crossings.
+use crate::research_camera::NODE_SIZE;
+use crate::{ResearchNode, ResearchTree};
+use eframe::egui;
+use std::collections::HashMap;
+
+// Where the first layer's column starts and the top of the tallest layer
+const ORIGIN: (f32, f32) = (300.0, 200.0);
+const LAYER_GAP: f32 = 260.0;
+const ROW_GAP: f32 = 110.0;
+// Barycenter passes (each one sweeps down then up)
+const ORDER_PASSES: usize = 12;
+
+// One slot in a layer: a node, or a dummy carrying a long edge through the layer
+#[derive(Clone, Debug, PartialEq)]
+enum Slot {
+    Node(String),
+    Dummy(usize),
+}
+
+// Suggested centre of every node in `nodes`, ignoring any coordinates they already have.
+// Prerequisites in other tabs don't affect the layout.
+pub fn layout_tab(nodes: &[ResearchNode]) -> HashMap<String, (f32, f32)> {
+    let index: HashMap<&str, usize> = nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+    let parents: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|n| n.prerequisites.iter().filter_map(|p| index.get(p.as_str()).copied()).collect())
+        .collect();
+
+    // Longest path from a root; cycles are broken where they are found
+    let mut depth: Vec<Option<usize>> = vec![None; nodes.len()];
+    fn depth_of(i: usize, parents: &[Vec<usize>], depth: &mut [Option<usize>], visiting: &mut Vec<usize>) -> usize {
+        if let Some(d) = depth[i] { return d; }
+        if visiting.contains(&i) { return 0; }
+        visiting.push(i);
+        let d = parents[i].iter().map(|&p| depth_of(p, parents, depth, visiting) + 1).max().unwrap_or(0);
+        visiting.pop();
+        depth[i] = Some(d);
+        d
+    }
+    for i in 0..nodes.len() {
+        depth_of(i, &parents, &mut depth, &mut Vec::new());
+    }
+    let depth: Vec<usize> = depth.into_iter().map(|d| d.unwrap_or(0)).collect();
+
+    // Layers in data order, with dummies so every edge joins neighbouring layers
+    let layer_count = depth.iter().max().map_or(0, |d| d + 1);
+    let mut layers: Vec<Vec<Slot>> = vec![Vec::new(); layer_count];
+    for (i, n) in nodes.iter().enumerate() {
+        layers[depth[i]].push(Slot::Node(n.id.clone()));
+    }
+    // (upper slot, lower slot) between layer l and l + 1
+    let mut edges: Vec<Vec<(Slot, Slot)>> = vec![Vec::new(); layer_count.saturating_sub(1)];
+    let mut dummies = 0;
+    for (child, ps) in parents.iter().enumerate() {
+        for &parent in ps {
+            let (from, to) = (depth[parent], depth[child]);
+            if from >= to { continue; }
+            let mut upper = Slot::Node(nodes[parent].id.clone());
+            for layer in from + 1..to {
+                let dummy = Slot::Dummy(dummies);
+                dummies += 1;
+                layers[layer].push(dummy.clone());
+                edges[layer - 1].push((upper, dummy.clone()));
+                upper = dummy;
+            }
+            edges[to - 1].push((upper, Slot::Node(nodes[child].id.clone())));
+        }
+    }
+
+    order_layers(&mut layers, &edges);
+
+    let tallest = layers.iter().map(Vec::len).max().unwrap_or(0);
+    let mut positions = HashMap::new();
+    for (l, layer) in layers.iter().enumerate() {
+        // Centre each layer against the tallest one
+        let top = ORIGIN.1 + (tallest - layer.len()) as f32 * ROW_GAP / 2.0;
+        for (row, slot) in layer.iter().enumerate() {
+            if let Slot::Node(id) = slot {
+                positions.insert(id.clone(), (ORIGIN.0 + l as f32 * LAYER_GAP, top + row as f32 * ROW_GAP));
+            }
+        }
+    }
+    positions
+}
+
+fn position_in(layer: &[Slot], slot: &Slot) -> f32 {
+    layer.iter().position(|s| s == slot).map_or(0.0, |p| p as f32)
+}
+
+// Edge crossings between two neighbouring layers
+fn crossings(upper: &[Slot], lower: &[Slot], edges: &[(Slot, Slot)]) -> usize {
+    let ends: Vec<(f32, f32)> = edges.iter().map(|(a, b)| (position_in(upper, a), position_in(lower, b))).collect();
+    let mut count = 0;
+    for (i, a) in ends.iter().enumerate() {
+        for b in &ends[i + 1..] {
+            if (a.0 - b.0) * (a.1 - b.1) < 0.0 { count += 1; }
+        }
+    }
+    count
+}
+
+fn total_crossings(layers: &[Vec<Slot>], edges: &[Vec<(Slot, Slot)>]) -> usize {
+    edges.iter().enumerate().map(|(l, e)| crossings(&layers[l], &layers[l + 1], e)).sum()
+}
+
+// Barycenter heuristic: sort each layer by the mean position of its neighbours in
+// the layer just placed, sweeping down and up, and keep the best ordering seen
+fn order_layers(layers: &mut [Vec<Slot>], edges: &[Vec<(Slot, Slot)>]) {
+    let mut best = layers.to_vec();
+    let mut best_count = total_crossings(layers, edges);
+    for pass in 0..ORDER_PASSES * 2 {
+        let down = pass % 2 == 0;
+        let order: Vec<usize> = if down { (1..layers.len()).collect() } else { (0..layers.len().saturating_sub(1)).rev().collect() };
+        for l in order {
+            let fixed = if down { layers[l - 1].clone() } else { layers[l + 1].clone() };
+            let layer_edges = if down { &edges[l - 1] } else { &edges[l] };
+            let mut keyed: Vec<(f32, usize, Slot)> = layers[l]
+                .iter()
+                .enumerate()
+                .map(|(i, slot)| {
+                    let neighbours: Vec<f32> = layer_edges
+                        .iter()
+                        .filter_map(|(a, b)| match down {
+                            true if b == slot => Some(position_in(&fixed, a)),
+                            false if a == slot => Some(position_in(&fixed, b)),
+                            _ => None,
+                        })
+                        .collect();
+                    // Slots without neighbours keep their place
+                    let key = if neighbours.is_empty() { i as f32 } else { neighbours.iter().sum::<f32>() / neighbours.len() as f32 };
+                    (key, i, slot.clone())
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            layers[l] = keyed.into_iter().map(|(_, _, slot)| slot).collect();
+        }
+        let count = total_crossings(layers, edges);
+        if count < best_count {
+            best_count = count;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+// Give every node without coordinates its place from the automatic layout. When a
+// tab mixes placed and unplaced nodes, an unplaced one moves down until it is clear.
+pub fn place_unpositioned(tree: &mut ResearchTree) {
+    for nodes in tree.values_mut() {
+        if nodes.iter().all(|n| n.x.is_some() && n.y.is_some()) { continue; }
+        let suggested = layout_tab(nodes);
+        let mut taken: Vec<egui::Pos2> = nodes.iter().filter_map(|n| Some(egui::pos2(n.x?, n.y?))).collect();
+        for n in nodes.iter_mut().filter(|n| n.x.is_none() || n.y.is_none()) {
+            let (sx, sy) = suggested.get(&n.id).copied().unwrap_or(ORIGIN);
+            let mut pos = egui::pos2(n.x.unwrap_or(sx), n.y.unwrap_or(sy));
+            while taken.iter().any(|&t| overlapping(pos, t)) {
+                pos.y += ROW_GAP;
+            }
+            n.x = Some(pos.x);
+            n.y = Some(pos.y);
+            taken.push(pos);
+        }
+    }
+}
+
+// Whether two nodes centred at `a` and `b` would draw on top of each other
+pub fn overlapping(a: egui::Pos2, b: egui::Pos2) -> bool {
+    (a.x - b.x).abs() < NODE_SIZE.x && (a.y - b.y).abs() < NODE_SIZE.y
+}
+
+// The tree with every node moved to its suggested position, for `--layout-research`
+pub fn suggested_layout(tree: &ResearchTree) -> ResearchTree {
+    let mut tree = tree.clone();
+    for nodes in tree.values_mut() {
+        let suggested = layout_tab(nodes);
+        for n in nodes.iter_mut() {
+            if let Some(&(x, y)) = suggested.get(&n.id) {
+                n.x = Some(x);
+                n.y = Some(y);
+            }
+        }
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn node(id: &str, prerequisites: &[&str], pos: Option<(f32, f32)>) -> ResearchNode {
+        ResearchNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            x: pos.map(|p| p.0),
+            y: pos.map(|p| p.1),
+            cost: IndexMap::new(),
+            research_time: None,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks: None,
+            unlocks_nodes: None,
+            unlocks_menu: None,
+        }
+    }
+
+    fn layer_of(positions: &HashMap<String, (f32, f32)>, id: &str) -> usize {
+        ((positions[id].0 - ORIGIN.0) / LAYER_GAP).round() as usize
+    }
+
+    #[test]
+    fn layers_follow_prerequisite_depth() {
+        let nodes = [
+            node("a", &[], None),
+            node("b", &["a"], None),
+            node("c", &["a"], None),
+            node("d", &["b", "c"], None),
+            node("e", &["a", "d"], None),
+        ];
+        let positions = layout_tab(&nodes);
+        let layers: Vec<usize> = ["a", "b", "c", "d", "e"].iter().map(|id| layer_of(&positions, id)).collect();
+        assert_eq!(layers, vec![0, 1, 1, 2, 3]);
+    }
+
+    #[test]
+    fn removable_crossing_is_removed() {
+        // In data order c sits above d, so a -> d and b -> c cross until reordered
+        let nodes = [node("a", &[], None), node("b", &[], None), node("c", &["b"], None), node("d", &["a"], None)];
+        let positions = layout_tab(&nodes);
+        let y = |id: &str| positions[id].1;
+        assert!((y("a") - y("b")) * (y("d") - y("c")) > 0.0, "edges a->d and b->c still cross");
+    }
+
+    #[test]
+    fn prerequisite_cycle_still_lays_out() {
+        let nodes = [node("a", &["b"], None), node("b", &["a"], None), node("c", &["a"], None), node("d", &["d"], None)];
+        let positions = layout_tab(&nodes);
+        assert_eq!(positions.len(), nodes.len());
+
+        let mut tree = ResearchTree::new();
+        tree.insert("Tab".to_string(), nodes.to_vec());
+        place_unpositioned(&mut tree);
+        assert!(tree["Tab"].iter().all(|n| n.x.is_some() && n.y.is_some()));
+    }
+
+    #[test]
+    fn unplaced_nodes_never_overlap_placed_ones() {
+        // The placed nodes sit exactly where the layout would put the unplaced ones
+        let mut tree = ResearchTree::new();
+        tree.insert(
+            "Tab".to_string(),
+            vec![
+                node("u1", &[], None),
+                node("u2", &[], None),
+                node("p1", &[], Some(ORIGIN)),
+                node("p2", &[], Some((ORIGIN.0, ORIGIN.1 + ROW_GAP))),
+                node("u3", &["u1"], None),
+                node("p3", &[], Some((ORIGIN.0 + LAYER_GAP, ORIGIN.1 + ROW_GAP))),
+            ],
+        );
+        place_unpositioned(&mut tree);
+        let nodes = &tree["Tab"];
+        assert_eq!(nodes[2].pos(), egui::pos2(ORIGIN.0, ORIGIN.1), "placed nodes keep their coordinates");
+        for (i, a) in nodes.iter().enumerate() {
+            for b in &nodes[i + 1..] {
+                assert!(!overlapping(a.pos(), b.pos()), "{} overlaps {}", a.id, b.id);
+            }
+        }
+    }
+}
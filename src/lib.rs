@@ -0,0 +1,2393 @@
+#![allow(deprecated)]
+// Save-file structs mirror the camelCase keys of existing saves
+#![allow(non_snake_case)]
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use rand::Rng;
+use std::fs;
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use rust_embed::RustEmbed;
+
+mod alchemy;
+mod crucible;
+mod flux;
+mod events;
+mod rng;
+mod commands;
+mod replay;
+pub mod sim;
+mod tui;
+mod cli;
+mod paths;
+mod mods;
+mod hotreload;
+mod aspects;
+mod codex;
+mod research;
+mod research_info;
+mod research_camera;
+mod layout;
+mod research_queue;
+use alchemy::{ActiveBuff, BuffEffect, Brewing, PotionBook};
+use events::{EventScheduler, Notification, WorldEvent};
+use rng::{GameRng, RngState};
+use commands::{GameCommand, Upgrade, UpgradeBook};
+use replay::Recorder;
+use research::{NodeVisibility, ResearchFilter};
+use research_queue::ResearchJob;
+use research_camera::{minimap_rect, tab_bounds, ResearchCamera, NODE_SIZE};
+
+#[derive(RustEmbed)]
+#[folder = "assets/aspects/"]
+#[include = "**/*.png"]
+struct Aspects;
+
+fn anyhow_to_eframe(e: anyhow::Error) -> eframe::Error {
+    eframe::Error::AppCreation(Box::new(std::io::Error::other(e.to_string())))
+}
+
+// Embedded static data
+const RECIPES_JSON: &str = include_str!("../data/recipes.json");
+const RESEARCH_JSON: &str = include_str!("../data/research.json");
+const POTIONS_JSON: &str = include_str!("../data/potions.json");
+const EVENTS_JSON: &str = include_str!("../data/events.json");
+const UPGRADES_JSON: &str = include_str!("../data/upgrades.json");
+const ASPECTS_JSON: &str = include_str!("../data/aspects.json");
+const DEFAULT_SAVE_JSON: &str = include_str!("../saves/default-save.json");
+
+// Fixed simulation step in seconds; timers advance in whole steps so replays line up
+const SIM_STEP: f32 = 0.1;
+
+// Vis Coagulator: how much overflow Vis condenses into one potentia crystal
+const COAGULATOR_VIS_PER_POTENTIA: u32 = 25;
+
+// Transmutation: percent of each recipe component returned when decomposing an aspect.
+// Research bonuses stack on the base rate, which is capped below 100% so it never nets a gain.
+const TRANSMUTE_BASE_RATE: u32 = 40;
+const TRANSMUTE_MAX_RATE: u32 = 90;
+const TRANSMUTE_BONUSES: [(&str, u32); 2] = [("catalyst_chains", 15), ("shard_husbandry", 15)];
+
+// Crafting: base success chance per recipe tier, and percent of components refunded on failure.
+// Research nodes add (success, refund) bonuses; a failed craft always leaves one perditio behind.
+const CRAFT_BASE_CHANCE: [(&str, u32); 3] = [("secondary", 90), ("tertiary", 75), ("quaternary", 60)];
+const CRAFT_BASE_REFUND: u32 = 20;
+const CRAFT_BONUSES: [(&str, u32, u32); 4] = [
+    ("shard_husbandry", 0, 30),
+    ("attunement_channels", 10, 0),
+    ("aura_weaving", 5, 0),
+    ("solvent_refinement", 0, 20),
+];
+
+// Plain research `unlocks` entries that `apply_unlocks` understands; anything else needs a
+// `recipe:` or `aspect:` prefix
+const UNLOCK_FLAGS: [&str; 11] = [
+    "secondary_crystals",
+    "tertiary_crystals",
+    "quaternary_crystals",
+    "vis_conversion",
+    "auto_clicking",
+    "advancedRunes",
+    "vis_coagulator",
+    "transmutation",
+    "alchemy",
+    "crucible",
+    "flux_wards",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Frontend {
+    Egui,
+    Terminal,
+}
+
+// Entry point for the game binary: parses launch flags, then runs the egui app
+pub fn run() -> eframe::Result<()> {
+    run_frontend(Frontend::Egui)
+}
+
+// Like `run`, but `frontend` picks the default UI; `--tui` always selects the terminal one
+pub fn run_frontend(frontend: Frontend) -> eframe::Result<()> {
+    let opts = cli::LaunchOptions::parse(std::env::args().skip(1)).map_err(anyhow_to_eframe)?;
+    if opts.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let paths = paths::Paths::resolve(opts.portable);
+    // settings.json supplies defaults for the flags below
+    let config = paths.load_config().map_err(anyhow_to_eframe)?;
+    let mut data_dir = opts.data_dir.clone().or(config.data_dir);
+    // Dev mode reads the source tree's data folder so edits show up without a rebuild
+    if opts.dev && data_dir.is_none() && std::path::Path::new("data").is_dir() {
+        data_dir = Some("data".into());
+    }
+    let windowed = match (opts.windowed, config.windowed.as_deref()) {
+        (Some(size), _) => Some(size),
+        (None, Some(size)) => Some(cli::parse_size(size).with_context(|| format!("in {}", paths.config_file().display())).map_err(anyhow_to_eframe)?),
+        (None, None) => None,
+    };
+    let source = DataSource { data_dir, mods_dir: Some(paths.data_dir.join("mods")) };
+    let (data, data_errors) = match GameData::load(&source) {
+        // Dev mode lists the same problems a hot reload would
+        Ok(data) if opts.dev => {
+            let problems = data.problems();
+            (data, problems)
+        }
+        Ok(data) => (data, Vec::new()),
+        // In dev mode a broken file shouldn't stop the game; show it in the overlay instead
+        Err(e) if opts.dev => (GameData::embedded().map_err(anyhow_to_eframe)?, vec![format!("using embedded data: {:#}", e)]),
+        Err(e) => return Err(anyhow_to_eframe(e)),
+    };
+
+    // `--validate-data` reports broken references between the data files
+    if opts.validate_data {
+        let problems = data.problems();
+        for c in &data.mods.conflicts {
+            eprintln!("warning: mod conflict: {}", c);
+        }
+        for p in &problems {
+            eprintln!("{}", p);
+        }
+        if !problems.is_empty() {
+            return Err(anyhow_to_eframe(anyhow::anyhow!("{} problem(s) found in the game data", problems.len())));
+        }
+        println!("Game data OK");
+        return Ok(());
+    }
+
+    // `--layout-research` prints research.json with suggested coordinates for every node
+    if opts.layout_research {
+        let base = GameData::load_base(source.data_dir.as_deref()).map_err(anyhow_to_eframe)?;
+        let json = serde_json::to_string_pretty(&layout::suggested_layout(&base.research)).map_err(|e| anyhow_to_eframe(e.into()))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    // `--replay <log>` re-runs a recorded session headlessly and prints the final state
+    if let Some(path) = &opts.replay {
+        let report = replay::replay_file(path, data).map_err(anyhow_to_eframe)?;
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| anyhow_to_eframe(e.into()))?);
+        return Ok(());
+    }
+
+    if opts.save.is_none()
+        && let Some(old) = paths.migrate_legacy_save().map_err(anyhow_to_eframe)?
+    {
+        eprintln!("Moved {} to {}", old.display(), paths.save_file().display());
+    }
+    let path = opts.save.clone().unwrap_or_else(|| paths.save_file());
+    // Back up the save only when this launch can overwrite it; a plain export just reads it
+    if opts.export_save.is_none() || opts.reset || opts.import_save.is_some() {
+        paths::backup_save(&path, &paths.backup_dir()).map_err(anyhow_to_eframe)?;
+    }
+    if opts.reset {
+        write_save_file(&path, DEFAULT_SAVE_JSON.as_bytes()).map_err(anyhow_to_eframe)?;
+    }
+    if let Some(import) = &opts.import_save {
+        import_save(import, &path).map_err(anyhow_to_eframe)?;
+    }
+    if let Some(export) = &opts.export_save {
+        let json = serde_json::to_vec_pretty(&load_save(&path)).map_err(|e| anyhow_to_eframe(e.into()))?;
+        std::fs::write(export, json).map_err(|e| anyhow_to_eframe(e.into()))?;
+        return Ok(());
+    }
+    let mut save: Savefile = load_or_create_save(&path);
+    // `--seed <n>` restarts the RNG stream from a fixed seed
+    if let Some(seed) = opts.seed {
+        save.rng = RngState { seed: Some(seed), word_pos: 0 };
+    }
+
+    let saved_at = save.saved_at;
+    let mut app = Clicker::from_save_with_data(save, data);
+    app.save_path = path;
+    app.catch_up_research(saved_at);
+    if opts.dev {
+        app.dev_errors = data_errors;
+        app.dev = Some(hotreload::DevWatcher::new(source.data_dir.iter().chain(source.mods_dir.iter()).cloned().collect()));
+    }
+    app.data_source = source;
+    // `--record <log>` writes every command of this session for later replay
+    if let Some(rec) = opts.record {
+        app.recorder = Some(Recorder::start(rec, &app));
+    }
+
+    if opts.headless {
+        return cli::run_headless(app).map_err(anyhow_to_eframe);
+    }
+    if frontend == Frontend::Terminal || opts.tui || config.tui {
+        return tui::run(app).map_err(anyhow_to_eframe);
+    }
+
+    let viewport = match windowed {
+        Some(size) => egui::ViewportBuilder::default().with_inner_size(size),
+        // Start maximized so it adapts to any screen size dynamically
+        None => egui::ViewportBuilder::default().with_maximized(true),
+    };
+    let options = eframe::NativeOptions {
+        // Keep a sensible minimum so small screens are usable
+        viewport: viewport.with_min_inner_size([800.0, 600.0]),
+        ..Default::default()
+    };
+
+    eframe::run_native("Clicker Game", options, Box::new(move |_cc| Ok(Box::new(app))))
+}
+
+fn load_json<T: for<'de> Deserialize<'de>>(file_path: &str) -> Result<T> {
+    let data = fs::read_to_string(file_path)?;
+    let parsed: T = serde_json::from_str(&data)?;
+    Ok(parsed)
+}
+
+// Where game data comes from: the embedded copies, optionally replaced file by
+// file from `data_dir`, then with the mods in `mods_dir` layered on top
+#[derive(Default, Clone, Debug)]
+struct DataSource {
+    data_dir: Option<std::path::PathBuf>,
+    mods_dir: Option<std::path::PathBuf>,
+}
+
+// All static game data, parsed once at startup
+struct GameData {
+    recipes: RecipesFile,
+    research: ResearchTree,
+    potions: PotionBook,
+    world_events: Vec<WorldEvent>,
+    upgrades: UpgradeBook,
+    aspects: aspects::AspectRegistry,
+    // Aspect icons supplied by mods, checked before the embedded ones
+    icons: HashMap<String, std::path::PathBuf>,
+    mods: mods::ModReport,
+}
+
+impl GameData {
+    fn embedded() -> Result<Self> {
+        Self::load(&DataSource::default())
+    }
+
+    fn load(source: &DataSource) -> Result<Self> {
+        let mut data = Self::load_base(source.data_dir.as_deref())?;
+        if let Some(dir) = &source.mods_dir {
+            mods::apply_mods(&mut data, dir);
+        }
+        layout::place_unpositioned(&mut data.research);
+        Ok(data)
+    }
+
+    // Files present in `dir` replace their embedded copies
+    fn load_base(dir: Option<&std::path::Path>) -> Result<Self> {
+        fn file<T: for<'de> Deserialize<'de>>(dir: Option<&std::path::Path>, name: &str, embedded: &str) -> Result<T> {
+            match dir.map(|d| d.join(name)).filter(|p| p.exists()) {
+                Some(path) => load_json(&path.to_string_lossy()).with_context(|| format!("loading {}", path.display())),
+                None => serde_json::from_str(embedded).with_context(|| format!("parsing embedded {}", name)),
+            }
+        }
+        Ok(Self {
+            recipes: file(dir, "recipes.json", RECIPES_JSON)?,
+            research: file(dir, "research.json", RESEARCH_JSON)?,
+            potions: file(dir, "potions.json", POTIONS_JSON)?,
+            world_events: file(dir, "events.json", EVENTS_JSON)?,
+            upgrades: file(dir, "upgrades.json", UPGRADES_JSON)?,
+            aspects: file(dir, "aspects.json", ASPECTS_JSON)?,
+            icons: HashMap::new(),
+            mods: mods::ModReport::default(),
+        })
+    }
+
+    // Broken references between the data files, one message per problem
+    fn problems(&self) -> Vec<String> {
+        let mut problems: Vec<String> = self.mods.errors.iter().map(|e| format!("mod {}", e)).collect();
+        let aspects: HashSet<&str> = self.aspects.keys().map(|k| k.as_str()).collect();
+        let known_cost = |k: &str| aspects.contains(k) || matches!(k, "Vis" | "Soul" | "Souls");
+        let nodes: HashSet<&str> = self.research.values().flatten().map(|n| n.id.as_str()).collect();
+
+        if !self.aspects.values().any(|a| a.primal) {
+            problems.push("aspects.json: no primal aspects, so gathering can't find crystals".to_string());
+        }
+        for (id, a) in &self.aspects {
+            if a.color32().is_none() {
+                problems.push(format!("aspects.json: {} has colour '{}', expected #rrggbb", id, a.color));
+            }
+            if !self.icons.contains_key(id) && Aspects::get(&a.icon).is_none() {
+                problems.push(format!("aspects.json: {} icon '{}' not found in assets/aspects", id, a.icon));
+            }
+        }
+        for (category, items) in &self.recipes.crystals {
+            for (item, costs) in items {
+                match self.aspects.get(item) {
+                    None => problems.push(format!("recipes.json: {}/{} is missing from aspects.json", category, item)),
+                    Some(a) if &a.tier != category => {
+                        problems.push(format!("aspects.json: {} has tier '{}' but is crafted in '{}'", item, a.tier, category))
+                    }
+                    _ => {}
+                }
+                for k in costs.keys().filter(|k| !aspects.contains(k.as_str())) {
+                    problems.push(format!("recipes.json: {}/{} uses unknown aspect '{}'", category, item, k));
+                }
+                if costs.values().all(|&amt| amt == 0) {
+                    problems.push(format!("recipes.json: {}/{} has no cost", category, item));
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        for (tab, list) in &self.research {
+            for n in list {
+                if !seen.insert(n.id.as_str()) {
+                    problems.push(format!("research.json: duplicate node id '{}'", n.id));
+                }
+                for k in n.cost.keys().filter(|k| !known_cost(k)) {
+                    problems.push(format!("research.json: {}/{} costs unknown aspect '{}'", tab, n.id, k));
+                }
+                for pre in n.prerequisites.iter().filter(|p| !nodes.contains(p.as_str())) {
+                    problems.push(format!("research.json: {}/{} requires unknown node '{}'", tab, n.id, pre));
+                }
+                for u in n.unlocks.iter().flatten() {
+                    if let Some(aspect) = u.strip_prefix("aspect:") {
+                        if !aspects.contains(aspect) {
+                            problems.push(format!("research.json: {}/{} reveals unknown aspect '{}'", tab, n.id, aspect));
+                        }
+                    } else if let Some(item) = u.strip_prefix("recipe:") {
+                        if !self.recipes.crystals.values().any(|items| items.contains_key(item)) {
+                            problems.push(format!("research.json: {}/{} unlocks unknown recipe '{}'", tab, n.id, item));
+                        }
+                    } else if !UNLOCK_FLAGS.contains(&u.as_str()) {
+                        problems.push(format!("research.json: {}/{} has unknown unlock '{}'", tab, n.id, u));
+                    }
+                }
+                for next in n.unlocks_nodes.iter().flatten().filter(|p| !nodes.contains(p.as_str())) {
+                    problems.push(format!("research.json: {}/{} reveals unknown node '{}'", tab, n.id, next));
+                }
+                if let Some(menu) = n.unlocks_menu.as_ref().filter(|m| !self.research.contains_key(*m)) {
+                    problems.push(format!("research.json: {}/{} opens unknown tab '{}'", tab, n.id, menu));
+                }
+                if n.research_time.is_some_and(|t| !(t >= 0.0 && t.is_finite())) {
+                    problems.push(format!("research.json: {}/{} has an invalid research_time", tab, n.id));
+                }
+            }
+            for (i, a) in list.iter().enumerate() {
+                for b in list[i + 1..].iter().filter(|b| layout::overlapping(a.pos(), b.pos())) {
+                    problems.push(format!("research.json: {}/{} overlaps {} (try --layout-research)", tab, a.id, b.id));
+                }
+            }
+        }
+        problems.extend(research::structure_problems(&self.research));
+        for (id, p) in &self.potions {
+            for k in p.cost.keys().filter(|k| !known_cost(k)) {
+                problems.push(format!("potions.json: {} costs unknown aspect '{}'", id, k));
+            }
+            if let Some(node) = p.requires.as_ref().filter(|r| !nodes.contains(r.as_str())) {
+                problems.push(format!("potions.json: {} requires unknown node '{}'", id, node));
+            }
+        }
+        for e in &self.world_events {
+            if let Some(node) = e.condition.requires_node.as_ref().filter(|r| !nodes.contains(r.as_str())) {
+                problems.push(format!("events.json: {} requires unknown node '{}'", e.id, node));
+            }
+            for k in e.effect.aspects().iter().filter(|k| !aspects.contains(k.as_str())) {
+                problems.push(format!("events.json: {} grants unknown aspect '{}'", e.id, k));
+            }
+        }
+        for id in self.upgrades.keys().filter(|id| !Upgrade::ALL.iter().any(|u| u.id() == id.as_str())) {
+            problems.push(format!("upgrades.json: unknown upgrade '{}'", id));
+        }
+        for u in Upgrade::ALL.iter().filter(|u| !self.upgrades.contains_key(u.id())) {
+            problems.push(format!("upgrades.json: missing upgrade '{}'", u.id()));
+        }
+        problems
+    }
+}
+
+// Write a save, creating its directory on first use
+fn write_save_file(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    std::fs::write(path, bytes).with_context(|| format!("writing {}", path.display()))
+}
+
+// The save at `path`, or a fresh one when there is none; nothing is written
+fn load_save(path: &std::path::Path) -> Savefile {
+    match std::fs::read_to_string(path) {
+        Ok(s) => serde_json::from_str(&s).unwrap_or_default(),
+        Err(_) => serde_json::from_str(DEFAULT_SAVE_JSON).unwrap_or_default(),
+    }
+}
+
+fn load_or_create_save(path: &std::path::Path) -> Savefile {
+    if !path.exists() {
+        let _ = write_save_file(path, DEFAULT_SAVE_JSON.as_bytes());
+    }
+    load_save(path)
+}
+
+fn save_game(app: &Clicker) -> anyhow::Result<()> {
+    let mut save = build_save(app);
+    save.saved_at = research_queue::unix_now();
+    let json = serde_json::to_vec_pretty(&save)?;
+    write_save_file(&app.save_path, &json)
+}
+
+// Check that `from` is a readable save before it replaces the one at `to`
+fn import_save(from: &std::path::Path, to: &std::path::Path) -> Result<()> {
+    let text = std::fs::read_to_string(from).with_context(|| format!("reading {}", from.display()))?;
+    let save: Savefile = serde_json::from_str(&text).with_context(|| format!("{} is not a valid save", from.display()))?;
+    write_save_file(to, &serde_json::to_vec_pretty(&save)?)
+}
+
+// Snapshot everything that affects gameplay; `from_save_with_data` restores it
+fn build_save(app: &Clicker) -> Savefile {
+    let mut save = Savefile::default();
+    save.inventory.Vis = app.vis;
+    save.inventory.crystals = app.crystals.clone();
+    save.inventory.visBuffer = app.vis_buffer;
+    save.unlocks = app.unlocks.clone();
+    save.settings.overflowMode = app.overflow_mode;
+    save.progress.overflowCaptured = app.overflow_captured;
+    save.progress.overflowLost = app.overflow_lost;
+    save.progress.totalClicks = app.total_clicks;
+    save.progress.totalVisEarned = app.total_vis_earned;
+    save.upgrades.visClickAmount = app.visClickAmount;
+    save.upgrades.crystalClickAmount = app.crystalClickAmount;
+    save.upgrades.maxVis = app.maxVis;
+    save.upgrades.autoClickInterval = app.autoClickInterval;
+    // Persist research progress (sorted so identical runs produce identical saves)
+    save.unlocked_nodes = sorted(&app.unlocked_nodes);
+    save.unlocked_recipes = sorted(&app.unlocked_recipes);
+    save.unlocked_research_tabs = sorted(&app.unlocked_research_tabs);
+    save.discovered_aspects = sorted(&app.discovered_aspects);
+    save.revealed_nodes = sorted(&app.revealed_nodes);
+    save.progress.crafted = app.crafted.clone();
+    save.researching = app.researching.clone();
+    save.research_queue = app.research_queue.clone();
+    // Persist alchemy progress
+    save.inventory.potions = app.potion_stock.clone();
+    save.brewing = app.brewing.clone();
+    save.active_buffs = app.active_buffs.clone();
+    save.crucible = app.crucible.clone();
+    save.rng = app.rng.state();
+    save.progress.flux = app.flux;
+    save.progress.fluxVisPenalty = app.flux_vis_penalty;
+    save.progress.fluxPenaltyTimer = app.flux_penalty_timer;
+    save
+}
+
+// The default save on the embedded data with a fixed RNG seed, for tests
+#[cfg(test)]
+fn test_save(seed: u64) -> Savefile {
+    let mut save: Savefile = serde_json::from_str(DEFAULT_SAVE_JSON).expect("default save parses");
+    save.rng = RngState { seed: Some(seed), word_pos: 0 };
+    save
+}
+
+#[cfg(test)]
+fn test_clicker(save: Savefile) -> Clicker {
+    Clicker::from_save_with_data(save, GameData::embedded().expect("embedded data loads"))
+}
+
+fn sorted(set: &HashSet<String>) -> Vec<String> {
+    let mut v: Vec<String> = set.iter().cloned().collect();
+    v.sort();
+    v
+}
+
+fn safe_subtract(value: &mut u32, amount: u32) -> bool {
+    if *value >= amount {
+        *value -= amount;
+        true
+    } else {
+        false
+    }
+}
+
+enum MenuTab {
+    Gathering,
+    Upgrades,
+    Alchemy,
+    Crucible,
+    Thauminomicon,
+    Codex,
+    Equipment,
+    Achievements,
+    Settings,
+}
+
+struct Clicker {
+    unlocks: Unlocks,
+    vis: u32,
+    maxVis: u32,
+    crystals: IndexMap<String, u32>,
+    visClickAmount: u32,
+    crystalClickAmount: u32,
+    runeChance: u32,
+    // Vis overflow handling (Vis Coagulator)
+    overflow_mode: OverflowMode,
+    vis_buffer: u32,
+    overflow_potentia_progress: u32,
+    overflow_captured: u32,
+    overflow_lost: u32,
+    // Lifetime totals
+    total_clicks: u32,
+    total_vis_earned: u32,
+    // Result of the last crafting attempt, shown under the recipe cards
+    craft_message: String,
+    // (success %, refund %) granted by equipped gear; the Equipment tab has nothing to equip yet
+    equipment_craft_bonus: (u32, u32),
+    // Result of the last research attempt: what was researched, or what's missing
+    research_message: Result<String, String>,
+    current_tab: MenuTab,
+    autoClickInterval: f32,
+    autoClickTimer: f32,
+    #[allow(dead_code)]
+    playTime: f32,
+    autosave_timer: f32,
+    // Thauminomicon state, from before the data-driven research tree
+    #[allow(dead_code)]
+    skills: Vec<SkillNode>,
+    #[allow(dead_code)]
+    cam_offset: egui::Vec2,
+    #[allow(dead_code)]
+    cam_zoom: f32,
+    // Research canvas camera per tab, and the canvas size from the last frame
+    research_cameras: HashMap<String, ResearchCamera>,
+    research_view_size: egui::Vec2,
+    research_search: String,
+    research_filter: ResearchFilter,
+    // Data
+    recipes: RecipesFile,
+    // Cached textures for crystal icons
+    textures: HashMap<String, egui::TextureHandle>,
+    research: ResearchTree,
+    current_research_tab: String,
+    unlocked_research_tabs: HashSet<String>,
+    unlocked_nodes: HashSet<String>,
+    // Recipes unlocked via research: item ids like "gelum", "metallum"
+    unlocked_recipes: HashSet<String>,
+    // Fog of war: nodes revealed by research, and reveal animations still playing
+    revealed_nodes: HashSet<String>,
+    reveal_anims: HashMap<String, f32>,
+    // Timed research under way and the nodes waiting to start after it
+    researching: Option<ResearchJob>,
+    research_queue: Vec<String>,
+    // Codex: aspects obtained at least once, lifetime crafts, and the open entry
+    discovered_aspects: HashSet<String>,
+    crafted: IndexMap<String, u32>,
+    codex_selected: Option<String>,
+    // Alchemy
+    potions: PotionBook,
+    potion_stock: IndexMap<String, u32>,
+    brewing: Option<Brewing>,
+    active_buffs: Vec<ActiveBuff>,
+    // Crucible: dissolved essentia waiting to form recipes
+    crucible: IndexMap<String, u32>,
+    crucible_decay_timer: f32,
+    crucible_log: Vec<String>,
+    // Flux pollution and its active penalties
+    flux: f32,
+    flux_event_timer: f32,
+    flux_vis_penalty: u32,
+    flux_penalty_timer: f32,
+    flux_message: String,
+    // World events
+    world_events: Vec<WorldEvent>,
+    events: EventScheduler,
+    notifications: Vec<Notification>,
+    // Single source of randomness for gameplay
+    rng: GameRng,
+    // Fixed-step simulation clock
+    sim_tick: u64,
+    sim_accumulator: f32,
+    // Active session recording (`--record`)
+    recorder: Option<Recorder>,
+    // Where `save_game` writes (`--save`)
+    save_path: std::path::PathBuf,
+    // Upgrade tuning from upgrades.json
+    upgrades: UpgradeBook,
+    aspects: aspects::AspectRegistry,
+    // Mod-supplied aspect icons and the mod list
+    icon_overrides: HashMap<String, std::path::PathBuf>,
+    mods: mods::ModReport,
+    // Where the data was loaded from, for reloading
+    data_source: DataSource,
+    // Dev mode file watcher and the problems found by the last reload
+    dev: Option<hotreload::DevWatcher>,
+    dev_errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Savefile {
+    player: Player,
+    inventory: Inventory,
+    settings: Settings,
+    unlocks: Unlocks,
+    progress: Progress,
+    upgrades: Upgrades,
+
+    // NEW: what to persist about research/thauminomicon
+    unlocked_nodes: Vec<String>,         // list of node IDs
+    unlocked_recipes: Vec<String>,       // recipe ids unlocked by research
+    unlocked_research_tabs: Vec<String>, // research tabs unlocked
+    // Aspects the player has ever obtained
+    discovered_aspects: Vec<String>,
+    // Research nodes revealed through `unlocks_nodes`
+    revealed_nodes: Vec<String>,
+    // Timed research under way and the nodes queued after it
+    researching: Option<ResearchJob>,
+    research_queue: Vec<String>,
+    // Unix time of the save, for offline research progress (0 when unknown)
+    saved_at: u64,
+
+    // Alchemy: potion in the cauldron and buffs still running
+    brewing: Option<Brewing>,
+    active_buffs: Vec<ActiveBuff>,
+    // Essentia still dissolved in the crucible
+    crucible: IndexMap<String, u32>,
+    // Seed and stream position of the game RNG
+    rng: RngState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+struct Player {
+    Charactername: String,
+    Title: String,
+    Level: u32,
+    Experience: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Inventory {
+    Vis: u32,
+    crystals: IndexMap<String, u32>,
+    // Condensed overflow Vis held by the Vis Coagulator
+    visBuffer: u32,
+    potions: IndexMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Settings {
+    colorScheme: String,
+    overflowMode: OverflowMode,
+}
+
+// What happens to Vis gained above maxVis once the Vis Coagulator is researched.
+// Without the research, overflow is always discarded.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum OverflowMode {
+    Discard,
+    #[default]
+    Buffer,
+    Potentia,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Unlocks {
+    advancedRunes: bool,
+    secondary_crystals: bool,
+    tertiary_crystals: bool,
+    quaternary_crystals: bool,
+    visConversion: bool,
+    autoCliking: bool,
+    vis_coagulator: bool,
+    transmutation: bool,
+    alchemy: bool,
+    crucible: bool,
+    flux_wards: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Progress {
+    totalClicks: u32,
+    totalVisEarned: u32,
+    overflowCaptured: u32,
+    overflowLost: u32,
+    flux: f32,
+    fluxVisPenalty: u32,
+    fluxPenaltyTimer: f32,
+    // Lifetime successful crafts per aspect
+    crafted: IndexMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+struct Upgrades {
+    visClickAmount: u32,
+    crystalClickAmount: u32,
+    autoClicker: u32,
+    // 0 in older saves means "not recorded"; the defaults are kept then
+    maxVis: u32,
+    autoClickInterval: f32,
+}
+
+#[derive(Deserialize, Debug)]
+struct RecipesFile {
+    // crystals.category -> item -> cost_map (preserve JSON order)
+    crystals: IndexMap<String, IndexMap<String, IndexMap<String, u32>>>,
+}
+
+#[allow(dead_code)]
+#[derive(Clone)]
+struct SkillNode {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    position: egui::Pos2,
+    unlocked: bool,
+    prerequisites: Vec<&'static str>,
+}
+
+// Research data-driven system
+#[derive(Serialize, Deserialize, Clone)]
+struct ResearchNode {
+    id: String,
+    name: String,
+    description: String,
+    // Canvas position of the node's centre; missing ones come from the automatic layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    x: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    y: Option<f32>,
+    cost: IndexMap<String, u32>,
+    // Seconds the research takes once started; missing means it completes at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    research_time: Option<f32>,
+    prerequisites: Vec<String>,
+    unlocks: Option<Vec<String>>,
+    unlocks_nodes: Option<Vec<String>>,
+    unlocks_menu: Option<String>,
+}
+
+type ResearchTree = IndexMap<String, Vec<ResearchNode>>; // category -> nodes
+
+impl ResearchNode {
+    // Always set once the data is loaded (see `layout::place_unpositioned`)
+    fn pos(&self) -> egui::Pos2 {
+        egui::pos2(self.x.unwrap_or_default(), self.y.unwrap_or_default())
+    }
+}
+
+impl Default for Clicker {
+    fn default() -> Self {
+        let crystals = IndexMap::new();
+        Self {
+            vis: 0,
+            maxVis: 50,
+            visClickAmount: 1,
+            crystalClickAmount: 1,
+            runeChance: 50,
+            overflow_mode: OverflowMode::default(),
+            vis_buffer: 0,
+            overflow_potentia_progress: 0,
+            overflow_captured: 0,
+            overflow_lost: 0,
+            total_clicks: 0,
+            total_vis_earned: 0,
+            craft_message: String::new(),
+            equipment_craft_bonus: (0, 0),
+            research_message: Ok(String::new()),
+            crystals,
+            autoClickInterval: 30.0,
+            autoClickTimer: 0.0,
+            playTime: 0.0,
+            autosave_timer: 0.0,
+            unlocks: Unlocks {
+                advancedRunes: false,
+                secondary_crystals: false,
+                tertiary_crystals: false,
+                quaternary_crystals: false,
+                visConversion: false,
+                autoCliking: false,
+                vis_coagulator: false,
+                transmutation: false,
+                alchemy: false,
+                crucible: false,
+                flux_wards: false,
+            },
+            current_tab: MenuTab::Gathering,
+            // Data-driven research now provides nodes; keep legacy skills empty
+            skills: Vec::new(),
+            cam_offset: egui::vec2(0.0, 0.0),
+            cam_zoom: 1.0,
+            research_cameras: HashMap::new(),
+            research_view_size: egui::vec2(1200.0, 800.0),
+            research_search: String::new(),
+            research_filter: ResearchFilter::default(),
+            recipes: RecipesFile { crystals: IndexMap::new() },
+            textures: HashMap::new(),
+            research: IndexMap::new(),
+            current_research_tab: "Crystallography".to_string(),
+            unlocked_research_tabs: {
+                let mut s = HashSet::new();
+                s.insert("Crystallography".to_string());
+                s
+            },
+            unlocked_nodes: HashSet::new(),
+            unlocked_recipes: HashSet::new(),
+            revealed_nodes: HashSet::new(),
+            reveal_anims: HashMap::new(),
+            researching: None,
+            research_queue: Vec::new(),
+            discovered_aspects: HashSet::new(),
+            crafted: IndexMap::new(),
+            codex_selected: None,
+            potions: IndexMap::new(),
+            potion_stock: IndexMap::new(),
+            brewing: None,
+            active_buffs: Vec::new(),
+            crucible: IndexMap::new(),
+            crucible_decay_timer: 0.0,
+            crucible_log: Vec::new(),
+            flux: 0.0,
+            flux_event_timer: 0.0,
+            flux_vis_penalty: 0,
+            flux_penalty_timer: 0.0,
+            flux_message: String::new(),
+            world_events: Vec::new(),
+            events: EventScheduler::default(),
+            notifications: Vec::new(),
+            rng: GameRng::from_entropy(),
+            sim_tick: 0,
+            sim_accumulator: 0.0,
+            recorder: None,
+            save_path: paths::Paths::resolve(false).save_file(),
+            upgrades: UpgradeBook::new(),
+            aspects: aspects::AspectRegistry::new(),
+            icon_overrides: HashMap::new(),
+            mods: mods::ModReport::default(),
+            data_source: DataSource::default(),
+            dev: None,
+            dev_errors: Vec::new(),
+        }
+    }
+}
+
+
+// Unlock result types
+#[derive(Debug)]
+enum UnlockError {
+    NotFound,
+    AlreadyUnlocked,
+    PrerequisitesMissing(Vec<String>),
+    // The node's research tab hasn't been opened yet
+    TabLocked(String),
+    Insufficient(Vec<Shortfall>),
+    // The node is being researched right now
+    InProgress,
+    // A timed research (the given node) is already under way
+    Busy(String),
+    AlreadyQueued,
+}
+
+// One cost entry the player can't cover yet
+#[derive(Debug, Clone)]
+struct Shortfall {
+    resource: String,
+    needed: u32,
+    have: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnlockOutcome {
+    Unlocked,
+    #[allow(dead_code)]
+    AlreadyUnlocked,
+    // Costs paid and the timer started; the node unlocks when it runs out
+    Started,
+}
+
+#[derive(Debug)]
+enum TransmuteError {
+    Locked,
+    NoRecipe,
+    // Recipe graph leads back to the item itself (e.g. sensus <- sensus + spiritus)
+    SelfReferential,
+    NotOwned,
+}
+
+impl Clicker {
+    #[allow(clippy::field_reassign_with_default)]
+    fn from_save_with_data(save: Savefile, data: GameData) -> Self {
+        let GameData { recipes, research, potions, world_events, upgrades, aspects, icons, mods } = data;
+        let mut clicker_default = Clicker::default();
+        // Restore inventory state
+        clicker_default.crystals = save.inventory.crystals;
+        clicker_default.vis = save.inventory.Vis;
+        clicker_default.vis_buffer = save.inventory.visBuffer;
+        clicker_default.unlocks = save.unlocks;
+        clicker_default.overflow_mode = save.settings.overflowMode;
+        clicker_default.overflow_captured = save.progress.overflowCaptured;
+        clicker_default.overflow_lost = save.progress.overflowLost;
+        clicker_default.total_clicks = save.progress.totalClicks;
+        clicker_default.total_vis_earned = save.progress.totalVisEarned;
+        clicker_default.recipes = recipes;
+        clicker_default.research = research;
+        clicker_default.potions = potions;
+        clicker_default.world_events = world_events;
+        clicker_default.upgrades = upgrades;
+        clicker_default.aspects = aspects;
+        clicker_default.icon_overrides = icons;
+        clicker_default.mods = mods;
+        clicker_default.potion_stock = save.inventory.potions;
+        clicker_default.brewing = save.brewing;
+        clicker_default.active_buffs = save.active_buffs;
+        clicker_default.crucible = save.crucible;
+        clicker_default.rng = GameRng::restore(save.rng);
+        clicker_default.flux = save.progress.flux;
+        clicker_default.flux_vis_penalty = save.progress.fluxVisPenalty;
+        clicker_default.flux_penalty_timer = save.progress.fluxPenaltyTimer;
+        // Initialize from saved upgrades
+        clicker_default.visClickAmount = save.upgrades.visClickAmount;
+        if save.upgrades.crystalClickAmount > 0 { clicker_default.crystalClickAmount = save.upgrades.crystalClickAmount; }
+        if save.upgrades.maxVis > 0 { clicker_default.maxVis = save.upgrades.maxVis; }
+        if save.upgrades.autoClickInterval > 0.0 { clicker_default.autoClickInterval = save.upgrades.autoClickInterval; }
+        // Populate runtime sets from save vectors
+        clicker_default.unlocked_nodes = save.unlocked_nodes.into_iter().collect();
+        clicker_default.unlocked_recipes = save.unlocked_recipes.into_iter().collect();
+        clicker_default.unlocked_research_tabs = save.unlocked_research_tabs.into_iter().collect();
+        clicker_default.discovered_aspects = save.discovered_aspects.into_iter().collect();
+        clicker_default.crafted = save.progress.crafted;
+        // Saves from before the codex: anything currently held counts as obtained
+        for (id, amount) in &clicker_default.crystals {
+            if *amount > 0 { clicker_default.discovered_aspects.insert(id.clone()); }
+        }
+        clicker_default.reveal_researched_aspects();
+        clicker_default.revealed_nodes = save.revealed_nodes.into_iter().collect();
+        clicker_default.reveal_researched_nodes();
+        // Nodes researched while the data misspelled some unlocks never set those flags
+        let granted: Vec<String> = clicker_default
+            .research
+            .values()
+            .flatten()
+            .filter(|n| clicker_default.unlocked_nodes.contains(&n.id))
+            .flat_map(|n| n.unlocks.iter().flatten().cloned())
+            .collect();
+        clicker_default.apply_unlocks(&granted);
+        clicker_default.researching = save.researching;
+        clicker_default.research_queue = save.research_queue;
+        // Ensure at least one research tab is unlocked
+        if clicker_default.unlocked_research_tabs.is_empty() {
+            if let Some((first_tab, _)) = clicker_default.research.iter().next() {
+                clicker_default.unlocked_research_tabs.insert(first_tab.clone());
+                clicker_default.current_research_tab = first_tab.clone();
+            }
+        } else if !clicker_default.unlocked_research_tabs.contains(&clicker_default.current_research_tab) {
+            // pick a valid current tab
+            if let Some(tab) = clicker_default.unlocked_research_tabs.iter().next() {
+                clicker_default.current_research_tab = tab.clone();
+            }
+        }
+        clicker_default
+    }
+
+        fn get_crystal_icon(&mut self, ctx: &egui::Context, name: &str) -> Option<&egui::TextureHandle> {
+        if self.textures.contains_key(name) {
+            return self.textures.get(name);
+        }
+
+        // Mod icons win over the embedded ones
+        let file = self.aspect(name).map(|a| a.icon.clone()).unwrap_or_else(|| format!("{}.png", name));
+        let bytes = match self.icon_overrides.get(name) {
+            Some(path) => std::fs::read(path).ok(),
+            None => Aspects::get(&file).map(|f| f.data.into_owned()),
+        };
+        if let Some(bytes) = bytes
+            && let Ok(image) = image::load_from_memory(&bytes)
+        {
+            let rgba = image.to_rgba8();
+            let size = [rgba.width() as usize, rgba.height() as usize];
+            let pixels = rgba.as_raw();
+            let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels);
+            let tex = ctx.load_texture(
+                format!("crystal_{}", name),
+                color_image,
+                egui::TextureOptions::LINEAR,
+            );
+            self.textures.insert(name.to_string(), tex);
+            return self.textures.get(name);
+        }
+
+        // Fallback for dev mode
+        let try_paths = [
+            format!("assets/aspects/{}", file),
+            format!("assets/apsects/{}", file),
+        ];
+        for path in try_paths.iter() {
+            if let Ok(bytes) = std::fs::read(path)
+                && let Ok(image) = image::load_from_memory(&bytes)
+            {
+                let rgba = image.to_rgba8();
+                let size = [rgba.width() as usize, rgba.height() as usize];
+                let pixels = rgba.as_raw();
+                let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels);
+                let tex = ctx.load_texture(
+                    format!("crystal_{}", name),
+                    color_image,
+                    egui::TextureOptions::LINEAR,
+                );
+                self.textures.insert(name.to_string(), tex);
+                return self.textures.get(name);
+            }
+        }
+
+        None
+    }
+
+    fn category_unlocked(&self, category: &str) -> bool {
+        match category {
+            "secondary" => self.unlocks.secondary_crystals,
+            "tertiary" => self.unlocks.tertiary_crystals,
+            "quaternary" => self.unlocks.quaternary_crystals,
+            _ => true,
+        }
+    }
+
+    // Recipe lookup across all crystal categories
+    fn recipe_for(&self, item: &str) -> Option<&IndexMap<String, u32>> {
+        self.recipes.crystals.values().find_map(|items| items.get(item))
+    }
+
+    // True if decomposing `item` can eventually yield `item` again
+    fn recipe_is_cyclic(&self, item: &str) -> bool {
+        let mut stack: Vec<&str> = match self.recipe_for(item) {
+            Some(costs) => costs.keys().map(|k| k.as_str()).collect(),
+            None => return false,
+        };
+        let mut seen: HashSet<&str> = HashSet::new();
+        while let Some(cur) = stack.pop() {
+            if cur == item { return true; }
+            if !seen.insert(cur) { continue; }
+            if let Some(costs) = self.recipe_for(cur) {
+                stack.extend(costs.keys().map(|k| k.as_str()));
+            }
+        }
+        false
+    }
+
+    fn transmute_rate(&self) -> u32 {
+        let bonus: u32 = TRANSMUTE_BONUSES
+            .iter()
+            .filter(|(node, _)| self.unlocked_nodes.contains(*node))
+            .map(|(_, b)| *b)
+            .sum();
+        (TRANSMUTE_BASE_RATE + bonus + self.buff_bonus(BuffEffect::TransmuteRate)).min(TRANSMUTE_MAX_RATE)
+    }
+
+    // Components returned by decomposing one `item` at the current rate
+    fn transmute_yield(&self, item: &str) -> Result<Vec<(String, u32)>, TransmuteError> {
+        if !self.unlocks.transmutation { return Err(TransmuteError::Locked); }
+        let costs = self.recipe_for(item).ok_or(TransmuteError::NoRecipe)?;
+        if self.recipe_is_cyclic(item) { return Err(TransmuteError::SelfReferential); }
+        let rate = self.transmute_rate();
+        Ok(costs
+            .iter()
+            .filter(|(k, _)| k.as_str() != "Vis")
+            .map(|(k, &amt)| (k.clone(), amt * rate / 100))
+            .collect())
+    }
+
+    fn transmute(&mut self, item: &str) -> Result<(), TransmuteError> {
+        let yields = self.transmute_yield(item)?;
+        let owned = self.crystals.get_mut(item).map(|have| safe_subtract(have, 1)).unwrap_or(false);
+        if !owned { return Err(TransmuteError::NotOwned); }
+        for (component, amt) in yields {
+            self.add_aspect(&component, amt);
+        }
+        Ok(())
+    }
+
+    // Crafting stats: (success %, refund %) from research plus equipped gear
+    fn craft_bonuses(&self) -> (u32, u32) {
+        CRAFT_BONUSES
+            .iter()
+            .filter(|(node, _, _)| self.unlocked_nodes.contains(*node))
+            .fold(self.equipment_craft_bonus, |(c, r), (_, dc, dr)| (c + dc, r + dr))
+    }
+
+    fn craft_chance(&self, category: &str) -> u32 {
+        let base = CRAFT_BASE_CHANCE
+            .iter()
+            .find(|(cat, _)| *cat == category)
+            .map(|(_, c)| *c)
+            .unwrap_or(100);
+        (base + self.craft_bonuses().0 + self.buff_bonus(BuffEffect::CraftChance)).min(100)
+    }
+
+    fn craft_refund_rate(&self) -> u32 {
+        (CRAFT_BASE_REFUND + self.craft_bonuses().1).min(100)
+    }
+
+    fn craft(&mut self, category: &str, name: &str) {
+        let Some(costs) = self.recipes.crystals.get(category).and_then(|items| items.get(name)).cloned() else { return; };
+        if !self.can_afford_cost(&costs) { return; }
+        self.spend_cost(&costs);
+
+        if self.rng.gen_range(0..100) < self.craft_chance(category) {
+            self.add_aspect(name, 1);
+            *self.crafted.entry(name.to_string()).or_insert(0) += 1;
+            self.craft_message = format!("Crafted {}", self.aspect_name(name));
+            return;
+        }
+
+        // Failure: refund part of the components and leave a perditio byproduct
+        self.add_flux(flux::FLUX_CRAFT_FAILURE);
+        let rate = self.craft_refund_rate();
+        let mut refunded: Vec<String> = Vec::new();
+        for (req, amt) in costs.iter() {
+            if req == "Vis" { continue; }
+            let back = amt * rate / 100;
+            if back > 0 {
+                self.add_aspect(req, back);
+                refunded.push(format!("{} x{}", self.aspect_name(req), back));
+            }
+        }
+        self.add_aspect("perditio", 1);
+        let (name, perditio) = (self.aspect_name(name), self.aspect_name("perditio"));
+        self.craft_message = if refunded.is_empty() {
+            format!("Crafting {} failed (+1 {})", name, perditio)
+        } else {
+            format!("Crafting {} failed, recovered {} (+1 {})", name, refunded.join(", "), perditio)
+        };
+    }
+
+    // Research system helpers
+    fn can_unlock_node(&self, id: &str) -> bool {
+        if self.unlocked_nodes.contains(id) || self.researching.as_ref().is_some_and(|job| job.node == id) {
+            return false;
+        }
+        // find node; prerequisites may sit in any tab, but the node's own tab must be open
+        let node = self
+            .research
+            .iter()
+            .find_map(|(tab, nodes)| nodes.iter().find(|n| n.id == id).map(|n| (tab, n)));
+        let Some((tab, node)) = node else { return false; };
+        self.unlocked_research_tabs.contains(tab) && node.prerequisites.iter().all(|pre| self.unlocked_nodes.contains(pre))
+    }
+
+    fn can_afford_cost(&self, cost: &IndexMap<String, u32>) -> bool {
+        for (k, &amt) in cost.iter() {
+            match k.as_str() {
+                "Vis" => { if self.vis < amt { return false; } }
+                // Souls removed from the game; ignore any legacy Soul cost keys
+                "Soul" | "Souls" => { /* ignore */ }
+                _ => {
+                    if self.crystals.get(k).copied().unwrap_or(0) < amt { return false; }
+                }
+            }
+        }
+        true
+    }
+
+    // How much of a cost resource the player holds
+    fn cost_have(&self, resource: &str) -> u32 {
+        match resource {
+            "Vis" => self.vis,
+            _ => self.crystals.get(resource).copied().unwrap_or(0),
+        }
+    }
+
+    // Every entry of `cost` the player is short on; empty when it's affordable
+    fn cost_shortfalls(&self, cost: &IndexMap<String, u32>) -> Vec<Shortfall> {
+        cost.iter()
+            .filter(|(k, _)| !matches!(k.as_str(), "Soul" | "Souls"))
+            .map(|(k, &needed)| Shortfall { resource: k.clone(), needed, have: self.cost_have(k) })
+            .filter(|s| s.have < s.needed)
+            .collect()
+    }
+
+    fn spend_cost(&mut self, cost: &IndexMap<String, u32>) {
+        for (k, &amt) in cost.iter() {
+            match k.as_str() {
+                "Vis" => { self.vis = self.vis.saturating_sub(amt); }
+                // Souls removed from the game; ignore any legacy Soul cost keys
+                "Soul" | "Souls" => { /* ignore */ }
+                _ => {
+                    if let Some(v) = self.crystals.get_mut(k) { *v = v.saturating_sub(amt); }
+                }
+            }
+        }
+    }
+
+    // Add Vis up to the cap; anything above it is routed by the overflow mode.
+    // Overflow that is thrown away turns into flux.
+    fn add_vis(&mut self, amount: u32) {
+        let room = self.effective_max_vis().saturating_sub(self.vis);
+        let gained = amount.min(room);
+        self.vis += gained;
+        self.total_vis_earned = self.total_vis_earned.saturating_add(gained);
+        let overflow = amount - gained;
+        if overflow == 0 { return; }
+
+        let mode = if self.unlocks.vis_coagulator { self.overflow_mode } else { OverflowMode::Discard };
+        match mode {
+            OverflowMode::Discard => {
+                self.overflow_lost = self.overflow_lost.saturating_add(overflow);
+                self.add_flux(overflow as f32 * flux::FLUX_PER_LOST_VIS);
+            }
+            OverflowMode::Buffer => {
+                // Buffer holds at most one extra tank of Vis
+                let stored = overflow.min(self.maxVis.saturating_sub(self.vis_buffer));
+                self.vis_buffer += stored;
+                self.overflow_captured = self.overflow_captured.saturating_add(stored);
+                self.overflow_lost = self.overflow_lost.saturating_add(overflow - stored);
+                self.add_flux((overflow - stored) as f32 * flux::FLUX_PER_LOST_VIS);
+            }
+            OverflowMode::Potentia => {
+                self.overflow_potentia_progress += overflow;
+                let condensed = self.overflow_potentia_progress / COAGULATOR_VIS_PER_POTENTIA;
+                self.overflow_potentia_progress %= COAGULATOR_VIS_PER_POTENTIA;
+                if condensed > 0 {
+                    self.add_aspect("potentia", condensed);
+                }
+                self.overflow_captured = self.overflow_captured.saturating_add(overflow);
+            }
+        }
+    }
+
+    // Per-click values after potion buffs
+    fn vis_per_click(&self) -> u32 {
+        (self.visClickAmount as f32 * self.buff_multiplier(BuffEffect::VisPerClick) * self.event_vis_multiplier()) as u32
+    }
+
+    fn crystal_chance(&self) -> u32 {
+        ((self.runeChance as f32 * self.buff_multiplier(BuffEffect::CrystalChance)) as u32).min(100)
+    }
+
+    fn crystals_per_find(&self) -> u32 {
+        (self.crystalClickAmount as f32 * self.buff_multiplier(BuffEffect::CrystalYield)) as u32
+    }
+
+    // Advance the simulation by real elapsed time, in whole SIM_STEPs
+    fn advance(&mut self, dt: f32) {
+        self.sim_accumulator += dt;
+        while self.sim_accumulator >= SIM_STEP {
+            self.sim_accumulator -= SIM_STEP;
+            self.step();
+        }
+    }
+
+    fn step(&mut self) {
+        self.sim_tick += 1;
+        self.tick_alchemy(SIM_STEP);
+        self.tick_research(SIM_STEP);
+        self.tick_crucible(SIM_STEP);
+        self.tick_flux(SIM_STEP);
+        self.tick_events(SIM_STEP);
+
+        if self.unlocks.autoCliking {
+            self.autoClickTimer += SIM_STEP;
+            if self.autoClickTimer >= self.autoClickInterval {
+                self.autoClickTimer -= self.autoClickInterval;
+                self.add_vis(self.vis_per_click());
+                self.add_flux(flux::FLUX_PER_AUTOCLICK);
+            }
+        }
+    }
+
+    // One press of "Conjure resources"
+    fn gather(&mut self) {
+        self.total_clicks = self.total_clicks.saturating_add(1);
+        self.add_vis(self.vis_per_click());
+
+        // Crystal gain: with the same chance as runes, add exactly one base crystal
+        if self.rng.gen_range(0..100) < self.crystal_chance() {
+            let base_crystals = self.primal_aspects();
+            if base_crystals.is_empty() { return; }
+            let chosen_idx = self.rng.gen_range(0..base_crystals.len());
+            let chosen = base_crystals[chosen_idx].as_str();
+            let found = self.crystals_per_find();
+            self.add_aspect(chosen, found);
+        }
+    }
+
+    // Move condensed Vis from the coagulator buffer back into the main pool
+    fn release_vis_buffer(&mut self) {
+        let room = self.effective_max_vis().saturating_sub(self.vis);
+        let moved = room.min(self.vis_buffer);
+        self.vis_buffer -= moved;
+        self.vis += moved;
+    }
+
+    fn apply_unlocks(&mut self, unlocks: &[String]) {
+        for u in unlocks {
+            match u.as_str() {
+                "secondary_crystals" => self.unlocks.secondary_crystals = true,
+                "tertiary_crystals" => self.unlocks.tertiary_crystals = true,
+                "quaternary_crystals" => self.unlocks.quaternary_crystals = true,
+                "vis_conversion" => self.unlocks.visConversion = true,
+                "auto_clicking" => self.unlocks.autoCliking = true,
+                "advancedRunes" => self.unlocks.advancedRunes = true,
+                "vis_coagulator" => self.unlocks.vis_coagulator = true,
+                "transmutation" => self.unlocks.transmutation = true,
+                "alchemy" => self.unlocks.alchemy = true,
+                "crucible" => self.unlocks.crucible = true,
+                "flux_wards" => self.unlocks.flux_wards = true,
+                _ => {
+                    if let Some(rest) = u.strip_prefix("recipe:") {
+                        self.unlocked_recipes.insert(rest.to_string());
+                    } else if let Some(aspect) = u.strip_prefix("aspect:") {
+                        self.discovered_aspects.insert(aspect.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    fn unlock_node(&mut self, id: &str) -> Result<UnlockOutcome, UnlockError> {
+        if self.unlocked_nodes.contains(id) {
+            return Err(UnlockError::AlreadyUnlocked);
+        }
+        // Locate node (and its category) for reading data
+        let (cat_key, idx) = {
+            let mut found: Option<(String, usize)> = None;
+            for (cat, nodes) in &self.research {
+                if let Some(i) = nodes.iter().position(|n| n.id == id) {
+                    found = Some((cat.clone(), i));
+                    break;
+                }
+            }
+            found.ok_or(UnlockError::NotFound)?
+        };
+        if !self.unlocked_research_tabs.contains(&cat_key) {
+            return Err(UnlockError::TabLocked(cat_key));
+        }
+        let node = &self.research.get(&cat_key).unwrap()[idx];
+        let cost = node.cost.clone();
+        let research_time = node.research_time.unwrap_or(0.0);
+        if let Some(job) = &self.researching {
+            if job.node == id { return Err(UnlockError::InProgress); }
+            if research_time > 0.0 { return Err(UnlockError::Busy(job.node.clone())); }
+        }
+
+        // Prerequisites
+        let missing: Vec<String> = node
+            .prerequisites
+            .iter()
+            .filter(|pre| !self.unlocked_nodes.contains(pre.as_str()))
+            .cloned()
+            .collect();
+        if !missing.is_empty() { return Err(UnlockError::PrerequisitesMissing(missing)); }
+
+        // Cost
+        let short = self.cost_shortfalls(&cost);
+        if !short.is_empty() { return Err(UnlockError::Insufficient(short)); }
+        self.spend_cost(&cost);
+
+        // Timed research unlocks when its timer runs out (see `tick_research`)
+        if research_time > 0.0 {
+            self.researching = Some(ResearchJob { node: id.to_string(), remaining: research_time });
+            return Ok(UnlockOutcome::Started);
+        }
+        self.complete_research(id);
+        Ok(UnlockOutcome::Unlocked)
+    }
+
+    // Mark a node researched and apply what it grants
+    fn complete_research(&mut self, id: &str) {
+        let Some(node) = self.research.values().flatten().find(|n| n.id == id) else { return; };
+        // Clone dynamic fields to avoid holding an immutable borrow across mutation
+        let unlocks = node.unlocks.clone();
+        let unlocks_nodes = node.unlocks_nodes.clone();
+        let unlocks_menu = node.unlocks_menu.clone();
+
+        self.unlocked_nodes.insert(id.to_string());
+        if let Some(unlocks) = &unlocks { self.apply_unlocks(unlocks); }
+        if let Some(tab) = &unlocks_menu { self.unlocked_research_tabs.insert(tab.clone()); }
+        if let Some(nodes) = &unlocks_nodes { self.reveal_nodes(nodes); }
+    }
+
+    fn show_research_book(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Thauminomicon").color(egui::Color32::WHITE));
+        ui.separator();
+
+        // Fallback: if current tab is missing (e.g., mismatched name), pick the first available
+        if !self.research.contains_key(&self.current_research_tab)
+            && let Some((first_tab, _)) = self.research.iter().next()
+        {
+            self.current_research_tab = first_tab.clone();
+            self.unlocked_research_tabs.insert(first_tab.clone());
+        }
+
+        if self.research.is_empty() {
+            ui.colored_label(egui::Color32::LIGHT_RED, "No research data found. Ensure data/research.json exists and loads correctly.");
+            return;
+        }
+
+        // Tabs for research categories
+        ui.horizontal(|ui| {
+            for tab in self.unlocked_research_tabs.clone().into_iter() {
+                if ui.add(styled_tab(&tab)).clicked() { self.current_research_tab = tab; }
+            }
+        });
+        self.show_research_search(ui);
+        self.show_research_message(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Fit all").clicked() { self.fit_research_view(); }
+            ui.label(egui::RichText::new("Drag or middle-drag to pan, Ctrl+wheel to zoom").color(egui::Color32::LIGHT_GRAY));
+        });
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        self.research_view_size = rect.size();
+        let tab = self.current_research_tab.clone();
+        let bounds = match self.research.get(&tab) { Some(nodes) => tab_bounds(nodes), None => return };
+
+        // Zoom toward the cursor; drag, middle-drag or scroll to pan
+        let dt = ui.input(|i| i.stable_dt);
+        let zoom_delta = ui.input(|i| i.zoom_delta());
+        let scroll_delta = ui.input(|i| i.smooth_scroll_delta);
+        let middle_down = ui.input(|i| i.pointer.middle_down());
+        let pointer_delta = ui.input(|i| i.pointer.delta());
+        let camera = self.research_camera(&tab);
+        if response.hovered() {
+            if zoom_delta != 1.0 {
+                let anchor = response.hover_pos().unwrap_or(rect.center());
+                camera.zoom_at(rect, anchor, zoom_delta);
+            } else if scroll_delta != egui::Vec2::ZERO {
+                camera.pan(scroll_delta);
+            }
+        }
+        if response.dragged() {
+            camera.pan(response.drag_delta());
+        } else if middle_down && response.hovered() {
+            camera.pan(pointer_delta);
+        }
+        camera.update(dt);
+        camera.clamp(rect.size(), bounds);
+        if camera.animating() { ui.ctx().request_repaint(); }
+        let cam = *camera;
+        self.tick_reveal_anims(dt);
+        if !self.reveal_anims.is_empty() { ui.ctx().request_repaint(); }
+
+        let to_screen = |p: egui::Pos2| cam.to_screen(rect, p);
+        let node_size = NODE_SIZE * cam.zoom;
+
+        self.load_cost_icons(ui.ctx(), &tab);
+        let visibility = self.research_visibility();
+        let visibility_of = |id: &str| visibility.get(id).copied().unwrap_or(NodeVisibility::Hidden);
+
+        let nodes = match self.research.get(&self.current_research_tab) { Some(v) => v, None => return };
+
+        // Hidden nodes are skipped; placeholders are drawn as "?" and the rest by state.
+
+        // Draw edge shafts first (under nodes) and collect arrowheads to draw on top
+        let mut arrowheads: Vec<(egui::Pos2, egui::Pos2, egui::Pos2, egui::Stroke)> = Vec::new();
+        for n in nodes.iter().filter(|n| visibility_of(&n.id) != NodeVisibility::Hidden) {
+            for pre in &n.prerequisites {
+                if let Some(pnode) = nodes.iter().find(|pn| &pn.id == pre && visibility_of(&pn.id) != NodeVisibility::Hidden) {
+                    let a = to_screen(pnode.pos());
+                    let b_center = to_screen(n.pos());
+                    let faint = visibility_of(&n.id) == NodeVisibility::Placeholder || visibility_of(&pnode.id) == NodeVisibility::Placeholder;
+                    let edge_color = if faint { egui::Color32::from_gray(45) } else { egui::Color32::DARK_GRAY };
+                    let stroke = egui::Stroke { width: 2.0, color: edge_color };
+                    let dir = b_center - a;
+                    let len = dir.length();
+                    if len > 1.0 {
+                        let u = dir / len;
+                        let arrow_len = 12.0 * cam.zoom.max(0.5);
+                        let arrow_half_w = 6.0 * cam.zoom.max(0.5);
+                        // Compute intersection with child node rectangle to place arrow just before it
+                        let hx = (node_size.x * 0.5).max(1.0);
+                        let hy = (node_size.y * 0.5).max(1.0);
+                        let ux = u.x.abs();
+                        let uy = u.y.abs();
+                        let tx = if ux > 1e-6 { hx / ux } else { f32::INFINITY };
+                        let ty = if uy > 1e-6 { hy / uy } else { f32::INFINITY };
+                        let t_edge = tx.min(ty);
+                        // Edge point on the child rect boundary (from center along -u)
+                        let edge_point = b_center - u * t_edge;
+                        let tip_inset = 4.0 * cam.zoom.max(0.5);
+                        let tip = edge_point - u * tip_inset;
+                        let base = tip - u * arrow_len;
+                        let perp = egui::vec2(-u.y, u.x);
+                        let left = base + perp * arrow_half_w;
+                        let right = base - perp * arrow_half_w;
+                        // main shaft up to base of arrow (so head sits on top of node)
+                        painter.line_segment([a, base], stroke);
+                        arrowheads.push((tip, left, right, stroke));
+                    } else {
+                        painter.line_segment([a, b_center], stroke);
+                    }
+                }
+            }
+        }
+
+        // Nodes under the minimap don't react to the pointer
+        let pointer_pos = response.hover_pos().filter(|pp| !minimap_rect(rect).contains(*pp));
+        let mut clicked: Option<String> = None;
+        for n in nodes.iter() {
+            let center = to_screen(n.pos());
+            match visibility_of(&n.id) {
+                NodeVisibility::Hidden => continue,
+                NodeVisibility::Placeholder => {
+                    let rect_node = egui::Rect::from_center_size(center, node_size);
+                    painter.rect_filled(rect_node, egui::Rounding::same(10), egui::Color32::from_gray(28));
+                    painter.rect_stroke(rect_node, egui::Rounding::same(10), egui::Stroke{width:1.0, color: egui::Color32::from_gray(70)}, egui::StrokeKind::Outside);
+                    painter.text(rect_node.center(), egui::Align2::CENTER_CENTER, "?", egui::FontId::proportional(22.0*cam.zoom), egui::Color32::from_gray(110));
+                    if pointer_pos.is_some_and(|pp| rect_node.contains(pp)) {
+                        let revealers: Vec<&str> = self
+                            .research
+                            .values()
+                            .flatten()
+                            .filter(|r| r.unlocks_nodes.iter().flatten().any(|x| x == &n.id) && visibility_of(&r.id) == NodeVisibility::Visible)
+                            .map(|r| r.name.as_str())
+                            .collect();
+                        egui::containers::show_tooltip_for(ui.ctx(), ui.layer_id(), egui::Id::new(format!("node_tt_{}", n.id)), &rect_node, |ui: &mut egui::Ui| {
+                            ui.label("Undiscovered research");
+                            ui.label(egui::RichText::new(format!("Research {} to reveal it.", revealers.join(" or "))).color(egui::Color32::LIGHT_GRAY));
+                        });
+                    }
+                    continue;
+                }
+                NodeVisibility::Visible => {}
+            }
+            let mut size = node_size;
+            // Newly revealed nodes grow and fade in with a fading gold ring
+            let reveal = self.reveal_progress(&n.id);
+            let ease = 1.0 - (1.0 - reveal).powi(3);
+            size *= 0.6 + 0.4 * ease;
+            let unlocked = self.unlocked_nodes.contains(&n.id);
+            let unlockable = !unlocked && self.can_unlock_node(&n.id) && self.can_afford_cost(&n.cost);
+            if unlockable {
+                let t = ui.ctx().input(|i| i.time as f32);
+                let scale = 1.05 + 0.02 * (t * 3.5).sin();
+                size *= scale;
+            }
+            let rect_node = egui::Rect::from_center_size(center, size);
+            let color = if unlocked { egui::Color32::from_rgb(50,190,90) } else if unlockable { egui::Color32::from_rgb(60,140,220) } else { egui::Color32::from_gray(50) };
+            // Nodes outside the search or filters are dimmed
+            let shown = self.node_matches_filter(n) && self.node_matches_query(n, &self.research_search);
+            let fade = if shown { ease } else { ease * 0.3 };
+            painter.rect_filled(rect_node, egui::Rounding::same(10), color.gamma_multiply(fade));
+            painter.rect_stroke(rect_node, egui::Rounding::same(10), egui::Stroke{width:2.0, color: egui::Color32::BLACK}, egui::StrokeKind::Outside);
+            if reveal < 1.0 {
+                let ring = rect_node.expand(6.0 * (1.0 - reveal) * cam.zoom);
+                painter.rect_stroke(ring, egui::Rounding::same(12), egui::Stroke{width:3.0, color: egui::Color32::GOLD.gamma_multiply(1.0 - reveal)}, egui::StrokeKind::Outside);
+            }
+            painter.text(rect_node.center(), egui::Align2::CENTER_CENTER, &n.name, egui::FontId::proportional(14.0*cam.zoom), egui::Color32::WHITE.gamma_multiply(fade));
+            // Timed research fills a bar along the bottom; queued nodes show their place
+            if let Some(progress) = self.research_progress(&n.id) {
+                let bar = egui::Rect::from_min_size(rect_node.left_bottom() - egui::vec2(0.0, 6.0 * cam.zoom), egui::vec2(rect_node.width() * progress, 6.0 * cam.zoom));
+                painter.rect_filled(bar, egui::Rounding::same(3), egui::Color32::GOLD.gamma_multiply(fade));
+            } else if let Some(i) = self.queued_position(&n.id) {
+                painter.text(rect_node.right_top() + egui::vec2(-8.0, 6.0) * cam.zoom, egui::Align2::RIGHT_TOP, format!("#{}", i + 1), egui::FontId::proportional(12.0*cam.zoom), egui::Color32::GOLD.gamma_multiply(fade));
+            }
+            if pointer_pos.is_some_and(|pp| rect_node.contains(pp)) {
+                egui::containers::show_tooltip_for(ui.ctx(), ui.layer_id(), egui::Id::new(format!("node_tt_{}", n.id)), &rect_node, |ui: &mut egui::Ui| {
+                    self.show_node_tooltip(ui, &self.current_research_tab, n);
+                });
+                // Clicking queues the node; one that can't be queued reports what's missing
+                if !unlocked && response.clicked() { clicked = Some(n.id.clone()); }
+            }
+        }
+        // Prerequisites from other tabs come in as labelled stubs on the node's left
+        let mut jump: Option<String> = None;
+        for n in nodes.iter().filter(|n| visibility_of(&n.id) == NodeVisibility::Visible) {
+            let links = self.cross_tab_prerequisites(&self.current_research_tab, n);
+            let left = to_screen(n.pos()) - egui::vec2(node_size.x * 0.5, 0.0);
+            for (k, (tab, pre)) in links.iter().enumerate() {
+                let dy = (k as f32 - (links.len() as f32 - 1.0) * 0.5) * 18.0 * cam.zoom;
+                let end = left + egui::vec2(-4.0 * cam.zoom, dy);
+                let start = end - egui::vec2(60.0 * cam.zoom, 0.0);
+                let done = self.unlocked_nodes.contains(&pre.id);
+                let color = if done { egui::Color32::from_rgb(50, 190, 90) } else { egui::Color32::from_rgb(200, 110, 90) };
+                let stroke = egui::Stroke { width: 2.0, color };
+                painter.line_segment([start, end], stroke);
+                let head = 6.0 * cam.zoom.max(0.5);
+                painter.line_segment([end, end + egui::vec2(-head, -head * 0.6)], stroke);
+                painter.line_segment([end, end + egui::vec2(-head, head * 0.6)], stroke);
+                let name = if visibility_of(&pre.id) == NodeVisibility::Visible { pre.name.as_str() } else { "???" };
+                let label = painter.text(start - egui::vec2(4.0, 0.0), egui::Align2::RIGHT_CENTER, format!("{} ▸ {}", tab, name), egui::FontId::proportional(12.0 * cam.zoom), color);
+                // Only tabs the player has opened can be jumped to
+                if self.unlocked_research_tabs.contains(tab.as_str()) && pointer_pos.is_some_and(|pp| label.expand(2.0).contains(pp)) {
+                    painter.rect_stroke(label.expand(2.0), egui::Rounding::same(3), egui::Stroke::new(1.0, color), egui::StrokeKind::Outside);
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                    if response.clicked() { jump = Some(pre.id.clone()); }
+                }
+            }
+        }
+        if let Some(node) = clicked { self.apply(GameCommand::QueueResearch { node }); }
+        if let Some(id) = jump { self.jump_to_node(&id); }
+        // Draw arrowheads on top of nodes so they are visible
+        for (tip, left, right, stroke) in arrowheads {
+            painter.line_segment([tip, left], stroke);
+            painter.line_segment([tip, right], stroke);
+        }
+        self.show_research_minimap(ui, rect, bounds, &visibility);
+    }
+
+    #[allow(dead_code)]
+    fn show_recipes(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Recipes").color(egui::Color32::WHITE));
+        ui.separator();
+        if self.recipes.crystals.is_empty() {
+            ui.colored_label(egui::Color32::LIGHT_RED, "No recipes loaded. Check data/recipes.json");
+            return;
+        }
+        let category_unlocked = |cat: &str, unlocks: &Unlocks| -> bool {
+            match cat {
+                "secondary" => unlocks.secondary_crystals,
+                "tertiary" => unlocks.tertiary_crystals,
+                "quaternary" => unlocks.quaternary_crystals,
+                _ => true,
+            }
+        };
+
+        // Clone the recipes to avoid borrowing self while rendering and loading textures
+        let recipes_snapshot = self.recipes.crystals.clone();
+        for (category, items) in recipes_snapshot {
+            if !category_unlocked(category.as_str(), &self.unlocks) { continue; }
+            ui.label(egui::RichText::new(&category).strong().color(egui::Color32::LIGHT_BLUE));
+            ui.horizontal_wrapped(|ui| {
+                for (name, costs) in items.iter() {
+                    // Card styling
+                    let (rect, _resp) = ui.allocate_exact_size(egui::vec2(220.0, 110.0), egui::Sense::hover());
+                    let painter = ui.painter();
+                    painter.rect_filled(rect, egui::Rounding::same(8), egui::Color32::from_rgb(40,40,50));
+                    painter.rect_stroke(
+                        rect,
+                        egui::Rounding::same(8),
+                        egui::Stroke{width:1.0, color: egui::Color32::DARK_GRAY},
+                        egui::StrokeKind::Outside,
+                    );
+                    // Contents
+                    let mut y = rect.min.y + 8.0;
+                    // Icon + name
+                    let tint = self.icon_tint(name);
+                    if let Some(tex) = self.get_crystal_icon(ui.ctx(), name) {
+                        let img_size = egui::vec2(20.0, 20.0);
+                        let img_rect = egui::Rect::from_min_size(rect.min + egui::vec2(8.0, 8.0), img_size);
+                        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                        painter.image(tex.id(), img_rect, uv, tint);
+                    }
+                    painter.text(rect.min + egui::vec2(36.0, 12.0), egui::Align2::LEFT_CENTER, self.aspect_name(name), egui::FontId::proportional(16.0), self.aspect_color(name));
+                    y += 28.0;
+                    // Costs with icons per required crystal/resource
+                    let mut x = rect.min.x + 8.0;
+                    let icon_size = egui::vec2(16.0, 16.0);
+                    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                    for (req, amt) in costs.iter() {
+                        if req == "Soul" || req == "Souls" { continue; }
+                        // Try an icon for the requirement (crystal). For Vis/Soul a text fallback is used.
+                        let tint = self.icon_tint(req);
+                        if let Some(tex) = self.get_crystal_icon(ui.ctx(), req) {
+                            let img_rect = egui::Rect::from_min_size(egui::pos2(x, y), icon_size);
+                            painter.image(tex.id(), img_rect, uv, tint);
+                            x += icon_size.x + 4.0;
+                            painter.text(egui::pos2(x, y + 2.0), egui::Align2::LEFT_TOP, format!("x{}", amt), egui::FontId::proportional(14.0), egui::Color32::LIGHT_GRAY);
+                            x += 28.0; // space before next requirement
+                        } else {
+                            // No icon: show text "req xamt"
+                            let label = format!("{} x{}", self.aspect_name(req), amt);
+                            painter.text(egui::pos2(x, y + 2.0), egui::Align2::LEFT_TOP, &label, egui::FontId::proportional(14.0), egui::Color32::LIGHT_GRAY);
+                            x += (label.len() as f32) * 7.5 + 12.0;
+                        }
+                    }
+                }
+            });
+            ui.separator();
+        }
+    }
+
+    #[allow(dead_code)]
+    fn can_unlock(&self, id: &str) -> bool {
+        let node = match self.skills.iter().find(|n| n.id == id) {
+            Some(n) => n,
+            None => return false,
+        };
+        if node.unlocked {
+            return false;
+        }
+        // All prerequisites must be unlocked
+        node.prerequisites.iter().all(|pre_id| {
+            self.skills.iter().any(|n| n.id == *pre_id && n.unlocked)
+        })
+    }
+
+    #[allow(dead_code)]
+    fn unlock_skill(&mut self, id: &str) -> Result<UnlockOutcome, UnlockError> {
+        // Locate node index for atomic mutation
+        let idx = self
+            .skills
+            .iter()
+            .position(|n| n.id == id)
+            .ok_or(UnlockError::NotFound)?;
+
+        if self.skills[idx].unlocked {
+            return Err(UnlockError::AlreadyUnlocked);
+        }
+
+        // Verify prerequisites; collect any missing for better diagnostics
+        let missing: Vec<String> = self.skills[idx]
+            .prerequisites
+            .iter()
+            .filter(|pre_id| !self.skills.iter().any(|n| n.id == **pre_id && n.unlocked))
+            .map(|s| s.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(UnlockError::PrerequisitesMissing(missing));
+        }
+
+        // Handle costs atomically before mutating state
+        match id {
+            "essence_control" => {
+                let needed = 50;
+                if self.vis < needed {
+                    return Err(UnlockError::Insufficient(vec![Shortfall { resource: "Vis".to_string(), needed, have: self.vis }]));
+                }
+                self.vis -= needed;
+                self.skills[idx].unlocked = true;
+                self.unlocks.secondary_crystals = true;
+            }
+            _ => {
+                self.skills[idx].unlocked = true;
+            }
+        }
+
+        Ok(UnlockOutcome::Unlocked)
+    }
+
+    #[allow(dead_code)]
+    fn show_thauminomicon(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Thauminomicon").color(egui::Color32::WHITE));
+        ui.separator();
+
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let canvas_size = egui::vec2(3000.0, 3000.0);
+                let (rect, _response) = ui.allocate_exact_size(canvas_size, egui::Sense::drag());
+                let painter = ui.painter_at(rect);
+
+                // Input handling: zoom with Ctrl+Wheel, pan with middle-mouse drag
+                let scroll_delta = ui.input(|i| i.smooth_scroll_delta.y);
+                let ctrl = ui.input(|i| i.modifiers.ctrl);
+                if ctrl && scroll_delta.abs() > 0.0 {
+                    let zoom_factor = (1.0 + (scroll_delta * 0.001)).clamp(0.5, 1.5);
+                    self.cam_zoom = (self.cam_zoom * zoom_factor).clamp(0.25, 3.0);
+                }
+                let pointer_delta = ui.input(|i| i.pointer.delta());
+                let middle_down = ui.input(|i| i.pointer.middle_down());
+                if middle_down {
+                    self.cam_offset += pointer_delta;
+                }
+
+                // Helpers
+                let to_screen = |p: egui::Pos2| -> egui::Pos2 {
+                    egui::pos2(
+                        rect.min.x + self.cam_offset.x + p.x * self.cam_zoom,
+                        rect.min.y + self.cam_offset.y + p.y * self.cam_zoom,
+                    )
+                };
+                let node_size = egui::vec2(180.0, 64.0) * self.cam_zoom;
+
+                // Draw edges first
+                for node in &self.skills {
+                    for pre in &node.prerequisites {
+                        if let Some(req) = self.skills.iter().find(|n| &n.id == pre) {
+                            let a = to_screen(req.position);
+                            let b = to_screen(node.position);
+                            let col = if req.unlocked { egui::Color32::from_rgb(80, 200, 120) } else { egui::Color32::GRAY };
+                            painter.line_segment([a, b], egui::Stroke { width: 2.0, color: col });
+                        }
+                    }
+                }
+
+                                // Draw nodes with enlarge/vibrate for unlockable and robust hover detection
+                let mut clicked_id: Option<String> = None;
+                let pointer_pos = ui.ctx().pointer_latest_pos();
+                for node in &self.skills {
+                    // Base center and size
+                    let mut center = to_screen(node.position);
+                    let mut size = node_size;
+                    let can_unlock = self.can_unlock(node.id);
+
+                    // Enlarge/vibrate effect for unlockable nodes
+                    if can_unlock {
+                        let t = ui.ctx().input(|i| i.time as f32);
+                        let scale = 1.08 + 0.02 * (t * 3.5).sin();
+                        size *= scale;
+                        let phase = (node.id.as_bytes()[0] as f32) * 0.37;
+                        let jiggle = egui::vec2(
+                            (t * 9.0 + phase).sin() * 1.5 * self.cam_zoom,
+                            (t * 11.0 + phase * 0.7).cos() * 1.5 * self.cam_zoom,
+                        );
+                        center += jiggle;
+                    }
+
+                    let rect_node = egui::Rect::from_center_size(center, size);
+                    let color = if node.unlocked {
+                        egui::Color32::from_rgb(50, 190, 90)
+                    } else if can_unlock {
+                        egui::Color32::from_rgb(60, 140, 220)
+                    } else {
+                        egui::Color32::from_gray(50)
+                    };
+
+                    painter.rect_filled(rect_node, egui::Rounding::same(10), color);
+                    painter.rect_stroke(
+                        rect_node,
+                        egui::Rounding::same(10),
+                        egui::Stroke { width: 2.0, color: egui::Color32::BLACK },
+                        egui::StrokeKind::Outside,
+                    );
+                    painter.text(
+                        rect_node.center(),
+                        egui::Align2::CENTER_CENTER,
+                        node.name,
+                        egui::FontId::proportional(16.0 * self.cam_zoom),
+                        egui::Color32::WHITE,
+                    );
+
+                    if pointer_pos.is_some_and(|pp| rect_node.contains(pp)) {
+                        egui::containers::show_tooltip_for(
+                            ui.ctx(),
+                            ui.layer_id(),
+                            egui::Id::new(format!("node_tt_{}", node.id)),
+                            &rect_node,
+                            |ui: &mut egui::Ui| {
+                                ui.label(node.description);
+                                if node.id == "essence_control" {
+                                    ui.separator();
+                                    ui.label(format!("Cost: 50 Vis (you have {})", self.vis));
+                                }
+                            },
+                        );
+                        if ui.input(|i| i.pointer.primary_clicked()) {
+                            clicked_id = Some(node.id.to_string());
+                        }
+                    }
+                }if let Some(id) = clicked_id {
+                    let _ = self.unlock_skill(&id);
+                }
+            });
+    }
+}
+
+// Helper function for main action buttons
+fn styled_button(label: &str) -> egui::Button<'_> {
+    egui::Button::new(
+        egui::RichText::new(label).color(egui::Color32::BLACK)
+    )
+        .fill(egui::Color32::WHITE)
+        .stroke(egui::Stroke::new(1.0, egui::Color32::BLACK))
+        .min_size([150.0, 50.0].into())
+}
+
+// Helper function for tab buttons (slightly smaller)
+fn styled_tab(label: &str) -> egui::Button<'_> {
+    egui::Button::new(
+        egui::RichText::new(label).color(egui::Color32::BLACK)
+    )
+        .fill(egui::Color32::WHITE)
+        .stroke(egui::Stroke::new(1.0, egui::Color32::BLACK))
+        .min_size([120.0, 40.0].into())
+}
+
+impl Clicker {
+    fn show_gathering(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Gather Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new(format!("Vis: {}/{}", self.vis, self.effective_max_vis())).color(egui::Color32::WHITE));
+        // Souls removed
+
+        // Clicking button
+        if self.unlocks.vis_coagulator {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Overflow:").color(egui::Color32::WHITE));
+                let mut mode = self.overflow_mode;
+                ui.radio_value(&mut mode, OverflowMode::Discard, "Discard");
+                ui.radio_value(&mut mode, OverflowMode::Buffer, "Buffer");
+                ui.radio_value(&mut mode, OverflowMode::Potentia, format!("Potentia ({} Vis each)", COAGULATOR_VIS_PER_POTENTIA));
+                if mode != self.overflow_mode {
+                    self.apply(GameCommand::SetOverflowMode { mode });
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("Buffer: {}/{}", self.vis_buffer, self.maxVis)).color(egui::Color32::WHITE));
+                if ui.add_enabled(self.vis_buffer > 0 && self.vis < self.effective_max_vis(), egui::Button::new("Release")).clicked() {
+                    self.apply(GameCommand::ReleaseVisBuffer);
+                }
+            });
+        }
+        ui.label(
+            egui::RichText::new(format!("Overflow captured: {}  lost: {}", self.overflow_captured, self.overflow_lost))
+                .color(egui::Color32::LIGHT_GRAY),
+        );
+
+        if ui.add(styled_button("Conjure resources")).clicked() {
+            self.apply(GameCommand::Gather);
+        }
+
+        // Crystal crafting (cards), one row per unlocked tier
+        let tiers = [
+            ("secondary", self.unlocks.secondary_crystals),
+            ("tertiary", self.unlocks.tertiary_crystals),
+            ("quaternary", self.unlocks.quaternary_crystals),
+        ];
+        for (category, unlocked) in tiers {
+            if unlocked { self.show_craft_cards(ui, category); }
+        }
+        if !self.craft_message.is_empty() {
+            ui.label(egui::RichText::new(&self.craft_message).color(egui::Color32::LIGHT_GRAY));
+        }
+
+        if self.unlocks.transmutation {
+            self.show_transmutation(ui);
+        }
+    }
+
+    fn show_craft_cards(&mut self, ui: &mut egui::Ui, category: &str) {
+        ui.separator();
+        ui.label(egui::RichText::new(format!("{} crystals", category)).color(egui::Color32::LIGHT_BLUE));
+        let Some(items) = self.recipes.crystals.get(category).cloned() else { return; };
+        let chance = self.craft_chance(category);
+        let gallery: Vec<(String, IndexMap<String, u32>)> = items.into_iter().collect();
+        ui.horizontal_wrapped(|ui| {
+            for (name, costs) in gallery.iter() {
+                let can_afford = self.can_afford_cost(costs);
+                // Card
+                let (rect, resp) = ui.allocate_exact_size(egui::vec2(220.0, 110.0), egui::Sense::click());
+                let painter = ui.painter();
+                let bg = if can_afford { egui::Color32::from_rgb(40,50,60) } else { egui::Color32::from_rgb(30,30,35) };
+                painter.rect_filled(rect, egui::Rounding::same(8), bg);
+                painter.rect_stroke(rect, egui::Rounding::same(8), egui::Stroke{width:1.0, color: egui::Color32::DARK_GRAY}, egui::StrokeKind::Outside);
+                // icon + name
+                let mut y = rect.min.y + 8.0;
+                let tint = self.icon_tint(name);
+                if let Some(tex) = self.get_crystal_icon(ui.ctx(), name) {
+                    let img_size = egui::vec2(20.0,20.0);
+                    let img_rect = egui::Rect::from_min_size(rect.min + egui::vec2(8.0,8.0), img_size);
+                    let uv = egui::Rect::from_min_max(egui::pos2(0.0,0.0), egui::pos2(1.0,1.0));
+                    painter.image(tex.id(), img_rect, uv, tint);
+                }
+                painter.text(rect.min + egui::vec2(36.0, 12.0), egui::Align2::LEFT_CENTER, self.aspect_name(name), egui::FontId::proportional(16.0), self.aspect_color(name));
+                y += 28.0;
+                // costs with icons
+                let mut x = rect.min.x + 8.0;
+                let icon_size = egui::vec2(16.0,16.0);
+                let uv = egui::Rect::from_min_max(egui::pos2(0.0,0.0), egui::pos2(1.0,1.0));
+                for (req, amt) in costs.iter() {
+                    let tint = self.icon_tint(req);
+                    if let Some(tex) = self.get_crystal_icon(ui.ctx(), req) {
+                        let img_rect = egui::Rect::from_min_size(egui::pos2(x,y), icon_size);
+                        painter.image(tex.id(), img_rect, uv, tint);
+                        x += icon_size.x + 4.0;
+                        let t = format!("x{}", amt);
+                        painter.text(egui::pos2(x,y+2.0), egui::Align2::LEFT_TOP, &t, egui::FontId::proportional(14.0), egui::Color32::LIGHT_GRAY);
+                        x += 28.0;
+                    } else {
+                        let label = format!("{} x{}", self.aspect_name(req), amt);
+                        painter.text(egui::pos2(x, y+2.0), egui::Align2::LEFT_TOP, &label, egui::FontId::proportional(14.0), egui::Color32::LIGHT_GRAY);
+                        x += (label.len() as f32)*7.5 + 12.0;
+                    }
+                }
+                // success chance
+                let chance_color = if chance >= 100 { egui::Color32::from_rgb(120,220,120) } else { egui::Color32::from_rgb(230,200,110) };
+                painter.text(egui::pos2(rect.min.x + 8.0, rect.max.y - 24.0), egui::Align2::LEFT_TOP, format!("Success: {}%", chance), egui::FontId::proportional(13.0), chance_color);
+                let resp = resp.on_hover_ui(|ui| self.show_aspect_info(ui, name));
+                if can_afford && resp.clicked() {
+                    self.apply(GameCommand::Craft { category: category.to_string(), name: name.clone() });
+                }
+            }
+        });
+    }
+
+    fn show_transmutation(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.label(egui::RichText::new(format!("transmutation ({}% returned)", self.transmute_rate())).color(egui::Color32::LIGHT_BLUE));
+        let owned: Vec<String> = self
+            .crystals
+            .iter()
+            .filter(|(_, v)| **v > 0)
+            .map(|(k, _)| k.clone())
+            .collect();
+        ui.horizontal_wrapped(|ui| {
+            for item in owned {
+                let Ok(yields) = self.transmute_yield(&item) else { continue; };
+                let parts: Vec<String> = yields.iter().map(|(k, amt)| format!("{} x{}", self.aspect_name(k), amt)).collect();
+                let label = format!("{} -> {}", self.aspect_name(&item), parts.join(", "));
+                if ui.add(egui::Button::new(label)).clicked() {
+                    self.apply(GameCommand::Transmute { item: item.clone() });
+                }
+            }
+        });
+    }
+
+    fn show_upgrades(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Upgrades Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Purchase upgrades to enhance clicks or crafting.").color(egui::Color32::WHITE));
+
+        // Upgrade 1 removed (used souls)
+
+        for upgrade in Upgrade::ALL {
+            if ui.add_enabled(self.upgrade_available(upgrade), styled_button(&self.upgrade_label(upgrade))).clicked() {
+                self.apply(GameCommand::BuyUpgrade { upgrade });
+            }
+        }
+    }
+    fn show_equipment(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Equipment Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Manage your equipment here.").color(egui::Color32::WHITE));
+        // Placeholder for equipment management
+    }
+    fn show_achievements(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Achievements Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Track your achievements here.").color(egui::Color32::WHITE));
+        // Placeholder for achievements tracking
+    }
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Settings Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Adjust your game settings here.").color(egui::Color32::WHITE));
+        // Include this in bug reports; `--seed` replays the same rolls
+        ui.label(egui::RichText::new(format!("RNG seed: {}", self.rng.seed())).color(egui::Color32::LIGHT_GRAY));
+        ui.separator();
+        if ui.add(styled_button("Save Game")).clicked() {
+            let _ = save_game(self);
+            self.flush_recording();
+        }
+        if ui.add(styled_button("Save and Exit")).clicked() {
+            let _ = save_game(self);
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        ui.separator();
+        self.show_mod_list(ui);
+        if let Some(rec) = &self.recorder {
+            ui.label(
+                egui::RichText::new(format!("Recording {} commands to {}", rec.recording.commands.len(), rec.path.display()))
+                    .color(egui::Color32::LIGHT_RED),
+            );
+        }
+    }
+    #[allow(dead_code)]
+    fn show_stat_breakdown(&mut self, ui: &mut egui::Ui) {
+        ui.heading(egui::RichText::new("Stat Break Down Menu").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new("Detailed stats of your progress.").color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new(format!("Vis Limit {}", self.maxVis)).color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new(format!("Vis per Click {}", self.visClickAmount)).color(egui::Color32::WHITE));
+        ui.label(egui::RichText::new(format!("Rune Chance {}", self.runeChance)).color(egui::Color32::WHITE));
+        // Placeholder for detailed stats
+    }
+}
+
+impl eframe::App for Clicker {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+
+        // inside update(...) where you have access to `ctx`
+        // autosave every 60 seconds
+        let dt = ctx.input(|i| i.unstable_dt);
+        self.autosave_timer += dt;
+        if self.autosave_timer >= 60.0 {
+            let _ = save_game(self);
+            self.flush_recording();
+            self.autosave_timer = 0.0;
+        }
+
+        self.advance(dt);
+        self.poll_dev_reload(dt);
+        // event timers run in the background
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        if self.brewing.is_some() || self.researching.is_some() || !self.active_buffs.is_empty() || !self.crucible.is_empty() || self.flux > 0.0 {
+            // keep countdowns moving
+            ctx.request_repaint();
+        }
+
+        if self.unlocks.autoCliking {
+            // request continuous repaints so update() runs each frame
+            ctx.request_repaint();
+        }
+
+        let bg_color = match self.current_tab {
+            MenuTab::Gathering => egui::Color32::from_rgb(40, 40, 80),
+            MenuTab::Upgrades => egui::Color32::from_rgb(30, 60, 30),
+            MenuTab::Alchemy => egui::Color32::from_rgb(45, 30, 55),
+            MenuTab::Crucible => egui::Color32::from_rgb(60, 35, 25),
+            MenuTab::Thauminomicon => egui::Color32::from_rgb(20, 20, 30),
+            MenuTab::Codex => egui::Color32::from_rgb(35, 30, 22),
+            MenuTab::Equipment => egui::Color32::from_rgb(30, 30, 60),
+            MenuTab::Achievements => egui::Color32::from_rgb(80, 40, 40),
+            MenuTab::Settings => egui::Color32::from_rgb(50, 30, 70),
+        };
+        // Top menu tabs
+        egui::TopBottomPanel::top("menu_panel")
+            .frame(
+                egui::Frame::default()
+                    .fill(egui::Color32::from_rgb(bg_color.r(), bg_color.g(), bg_color.b())) // Background color of the tab bar
+                    .inner_margin(egui::Margin::same(5)) // Optional padding
+            )
+            .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.add(styled_tab("Gather/Convert")).clicked() {
+                    self.current_tab = MenuTab::Gathering;
+                }
+                if ui.add(styled_tab("Upgrades")).clicked() {
+                    self.current_tab = MenuTab::Upgrades;
+                }
+                if self.unlocks.alchemy && ui.add(styled_tab("Alchemy")).clicked() {
+                    self.current_tab = MenuTab::Alchemy;
+                }
+                if self.unlocks.crucible && ui.add(styled_tab("Crucible")).clicked() {
+                    self.current_tab = MenuTab::Crucible;
+                }
+                if ui.add(styled_tab("Thauminomicon")).clicked() {
+                    self.current_tab = MenuTab::Thauminomicon;
+                }
+                if ui.add(styled_tab("Codex")).clicked() {
+                    self.current_tab = MenuTab::Codex;
+                }
+                if ui.add(styled_tab("Equipment")).clicked() {
+                    self.current_tab = MenuTab::Equipment;
+                }
+                if ui.add(styled_tab("Achievements")).clicked() {
+                    self.current_tab = MenuTab::Achievements;
+                }
+                if ui.add(styled_tab("Settings")).clicked() {
+                    self.current_tab = MenuTab::Settings;
+                }
+            });
+        });
+
+        // Active potion buffs and world events with countdowns
+        if !self.active_buffs.is_empty() || !self.events.active.is_empty() {
+            egui::TopBottomPanel::top("buff_strip").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    self.show_buff_strip(ui);
+                    self.show_event_strip(ui);
+                });
+            });
+        }
+
+        // Always-visible right side panel (inventory)
+        egui::SidePanel::right("inventory_panel")
+            .resizable(false)
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                ui.heading(egui::RichText::new("Inventory").color(egui::Color32::WHITE));
+                ui.separator();
+                self.show_flux_meter(ui);
+                ui.separator();
+                self.show_research_queue(ui);
+                ui.label(egui::RichText::new("Crystals").color(egui::Color32::WHITE));
+                // Avoid borrowing self immutably while calling a mutable method
+                let crystal_list: Vec<(String, u32)> = self
+                    .crystals
+                    .iter()
+                    .filter(|(k, _)| self.aspect_discovered(k))
+                    .map(|(k, v)| (k.clone(), *v))
+                    .collect();
+                for (crystal, amount) in crystal_list {
+                    ui.horizontal(|ui| {
+                        if let Some(tex) = self.get_crystal_icon(ui.ctx(), &crystal) {
+                            ui.add(egui::Image::new((tex.id(), egui::vec2(18.0, 18.0))));
+                        }
+                        ui.label(self.aspect_count_text(&crystal, amount))
+                            .on_hover_ui(|ui| self.show_aspect_info(ui, &crystal));
+                    });
+                }
+            });
+
+        // Central panel content
+        egui::CentralPanel::default()
+            .frame(
+                egui::Frame::default()
+                    .fill(egui::Color32::from_rgb(bg_color.r(), bg_color.g(), bg_color.b()))
+            )
+            .show(ctx, |ui| {
+                match self.current_tab {
+                    MenuTab::Gathering => self.show_gathering(ui),
+                    MenuTab::Upgrades => self.show_upgrades(ui),
+                    MenuTab::Alchemy => self.show_alchemy(ui),
+                    MenuTab::Crucible => self.show_crucible(ui),
+                    MenuTab::Thauminomicon => self.show_research_book(ui),
+                    MenuTab::Codex => self.show_codex(ui),
+                    MenuTab::Equipment => self.show_equipment(ui),
+                    MenuTab::Achievements => self.show_achievements(ui),
+                    MenuTab::Settings => self.show_settings(ui),
+                }
+            });
+
+        self.show_event_overlay(ctx);
+        self.show_dev_overlay(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.flush_recording();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stocked_clicker(seed: u64) -> Clicker {
+        let mut app = test_clicker(test_save(seed));
+        for aspect in ["aer", "ignis"] {
+            app.crystals.insert(aspect.to_string(), 1000);
+        }
+        app
+    }
+
+    fn held(app: &Clicker, aspect: &str) -> u32 {
+        app.crystals.get(aspect).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn craft_chance_and_refund_are_clamped() {
+        let mut app = stocked_clicker(1);
+        assert_eq!(app.craft_chance("secondary"), 90);
+        assert_eq!(app.craft_chance("unknown"), 100);
+        assert_eq!(app.craft_refund_rate(), CRAFT_BASE_REFUND);
+
+        app.equipment_craft_bonus = (50, 95);
+        assert_eq!(app.craft_chance("quaternary"), 100);
+        assert_eq!(app.craft_refund_rate(), 100);
+    }
+
+    #[test]
+    fn guaranteed_craft_succeeds() {
+        let mut app = stocked_clicker(1);
+        app.equipment_craft_bonus = (100, 0);
+        for _ in 0..20 {
+            app.craft("secondary", "lux");
+        }
+        assert_eq!(held(&app, "lux"), 20);
+        assert_eq!(held(&app, "aer"), 900);
+        assert_eq!(app.crafted.get("lux"), Some(&20));
+        assert_eq!(held(&app, "perditio"), 0);
+    }
+
+    #[test]
+    fn failed_craft_refunds_and_leaves_perditio() {
+        let mut app = stocked_clicker(7);
+        // Craft with the seeded RNG until one attempt fails
+        let failed = (0..100).any(|_| {
+            let lux = held(&app, "lux");
+            app.craft("secondary", "lux");
+            held(&app, "lux") == lux
+        });
+        assert!(failed, "no failure in 100 seeded crafts at 90%");
+
+        let crafts = app.crafted.get("lux").copied().unwrap_or(0);
+        // Each success costs 5 aer; the failure costs 5 and refunds 20% of that
+        assert_eq!(held(&app, "aer"), 1000 - 5 * crafts - 5 + 1);
+        assert_eq!(held(&app, "ignis"), held(&app, "aer"));
+        assert_eq!(held(&app, "perditio"), 1);
+        assert!(app.flux > 0.0);
+        assert!(app.craft_message.starts_with("Crafting"));
+    }
+
+    // Vis at the cap with the coagulator set to `mode`
+    fn full_tank(mode: OverflowMode) -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        app.unlocks.vis_coagulator = true;
+        app.overflow_mode = mode;
+        app.vis = app.maxVis;
+        app.flux = 0.0;
+        app
+    }
+
+    #[test]
+    fn discarded_overflow_is_lost_as_flux() {
+        let mut app = full_tank(OverflowMode::Discard);
+        app.add_vis(40);
+        assert_eq!((app.vis, app.overflow_lost, app.vis_buffer), (app.maxVis, 40, 0));
+        assert!((app.flux - 40.0 * flux::FLUX_PER_LOST_VIS).abs() < 1e-4);
+
+        // Without the coagulator every mode discards
+        let mut app = full_tank(OverflowMode::Potentia);
+        app.unlocks.vis_coagulator = false;
+        app.add_vis(40);
+        assert_eq!((app.overflow_lost, app.overflow_captured), (40, 0));
+    }
+
+    #[test]
+    fn buffered_overflow_stops_at_max_vis() {
+        let mut app = full_tank(OverflowMode::Buffer);
+        let max = app.maxVis;
+        app.add_vis(max - 10);
+        assert_eq!((app.vis_buffer, app.overflow_captured, app.overflow_lost), (max - 10, max - 10, 0));
+        assert_eq!(app.flux, 0.0);
+        app.add_vis(30);
+        assert_eq!((app.vis_buffer, app.overflow_captured, app.overflow_lost), (max, max, 20));
+        assert!((app.flux - 20.0 * flux::FLUX_PER_LOST_VIS).abs() < 1e-4);
+    }
+
+    #[test]
+    fn potentia_overflow_condenses_and_keeps_the_remainder() {
+        let mut app = full_tank(OverflowMode::Potentia);
+        app.add_vis(COAGULATOR_VIS_PER_POTENTIA * 2 + 5);
+        assert_eq!((held(&app, "potentia"), app.overflow_potentia_progress), (2, 5));
+        app.add_vis(COAGULATOR_VIS_PER_POTENTIA - 5);
+        assert_eq!((held(&app, "potentia"), app.overflow_potentia_progress), (3, 0));
+        assert_eq!((app.overflow_captured, app.overflow_lost), (COAGULATOR_VIS_PER_POTENTIA * 3, 0));
+        assert_eq!(app.flux, 0.0);
+    }
+
+    #[test]
+    fn transmutation_refuses_recipes_that_lead_back_to_the_item() {
+        let mut app = test_clicker(test_save(1));
+        assert!(matches!(app.transmute_yield("lux"), Err(TransmuteError::Locked)));
+        app.unlocks.transmutation = true;
+        assert!(matches!(app.transmute_yield("aer"), Err(TransmuteError::NoRecipe)));
+        // sensus lists itself as a component
+        assert!(matches!(app.transmute_yield("sensus"), Err(TransmuteError::SelfReferential)));
+        // alpha and beta only reach each other through a second step
+        let secondary = app.recipes.crystals.get_mut("secondary").unwrap();
+        secondary.insert("alpha".to_string(), IndexMap::from([("beta".to_string(), 5)]));
+        secondary.insert("beta".to_string(), IndexMap::from([("alpha".to_string(), 5), ("aer".to_string(), 5)]));
+        assert!(matches!(app.transmute_yield("alpha"), Err(TransmuteError::SelfReferential)));
+        assert!(app.transmute_yield("lux").is_ok());
+    }
+
+    #[test]
+    fn transmute_rate_is_capped() {
+        let mut app = stocked_clicker(1);
+        app.unlocks.transmutation = true;
+        assert_eq!(app.transmute_yield("lux").unwrap(), vec![("aer".to_string(), 2), ("ignis".to_string(), 2)]);
+
+        for (node, _) in TRANSMUTE_BONUSES {
+            app.unlocked_nodes.insert(node.to_string());
+        }
+        app.active_buffs.push(alchemy::ActiveBuff { potion: "test".to_string(), effect: BuffEffect::TransmuteRate, amount: 500.0, remaining: 60.0 });
+        assert_eq!(app.transmute_rate(), TRANSMUTE_MAX_RATE);
+        assert_eq!(app.transmute_yield("lux").unwrap(), vec![("aer".to_string(), 4), ("ignis".to_string(), 4)]);
+
+        assert!(matches!(app.transmute("lux"), Err(TransmuteError::NotOwned)));
+        app.crystals.insert("lux".to_string(), 1);
+        app.transmute("lux").unwrap();
+        assert_eq!((held(&app, "lux"), held(&app, "aer")), (0, 1004));
+    }
+
+    #[test]
+    fn every_unlock_flag_is_applied() {
+        let base = serde_json::to_value(build_save(&test_clicker(test_save(1)))).unwrap();
+        for flag in UNLOCK_FLAGS {
+            let mut app = test_clicker(test_save(1));
+            app.apply_unlocks(&[flag.to_string()]);
+            assert_ne!(serde_json::to_value(build_save(&app)).unwrap(), base, "{} changes nothing", flag);
+        }
+    }
+
+    #[test]
+    fn misspelled_unlocks_are_reported() {
+        let mut data = GameData::embedded().unwrap();
+        assert!(data.problems().is_empty());
+        let node = data.research.values_mut().flatten().next().unwrap();
+        node.unlocks = Some(vec!["autoCliking".to_string(), "recipe:nothing".to_string(), "alchemy".to_string()]);
+        let problems = data.problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].ends_with("has unknown unlock 'autoCliking'"));
+        assert!(problems[1].ends_with("unlocks unknown recipe 'nothing'"));
+    }
+
+    #[test]
+    fn craft_without_components_does_nothing() {
+        let mut app = test_clicker(test_save(1));
+        app.craft("secondary", "lux");
+        assert_eq!(held(&app, "lux"), 0);
+        assert_eq!(held(&app, "perditio"), 0);
+        assert!(app.craft_message.is_empty());
+    }
+}
@@ -0,0 +1,377 @@
+// Mods: folders of partial data files layered over the base game data in load
+// order. Each mod directory may contain any of recipes.json, research.json,
+// potions.json, events.json, upgrades.json, aspects.json and an aspects/ folder of icons,
+// plus an optional mod.json manifest. Later mods win; overlaps are reported.
+use crate::{load_json, Clicker, GameData, RecipesFile, ResearchTree};
+use crate::alchemy::PotionBook;
+use crate::aspects::AspectRegistry;
+use crate::commands::UpgradeBook;
+use crate::events::WorldEvent;
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Load order and enabled state, kept in the mods folder
+const LOAD_ORDER_FILE: &str = "mods.json";
+const MANIFEST_FILE: &str = "mod.json";
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct LoadOrder {
+    pub order: Vec<String>,
+    pub disabled: Vec<String>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+struct Manifest {
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModInfo {
+    // Folder name; what load order and conflicts refer to
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub enabled: bool,
+}
+
+// Outcome of layering mods, shown in the in-game mod list
+#[derive(Debug, Clone, Default)]
+pub struct ModReport {
+    pub dir: Option<PathBuf>,
+    // In load order, disabled ones included
+    pub mods: Vec<ModInfo>,
+    pub conflicts: Vec<String>,
+    // Mods that failed to load are skipped; the base game still starts
+    pub errors: Vec<String>,
+}
+
+// Tracks which mod last defined each entry so overrides can be reported
+#[derive(Default)]
+struct Owners(HashMap<(&'static str, String), String>);
+
+impl Owners {
+    fn claim(&mut self, report: &mut ModReport, kind: &'static str, key: &str, mod_id: &str) {
+        if let Some(prev) = self.0.insert((kind, key.to_string()), mod_id.to_string()) {
+            report.conflicts.push(format!("{} '{}': {} overrides {}", kind, key, mod_id, prev));
+        }
+    }
+}
+
+pub fn read_load_order(dir: &Path) -> LoadOrder {
+    std::fs::read_to_string(dir.join(LOAD_ORDER_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_load_order(dir: &Path, order: &LoadOrder) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(LOAD_ORDER_FILE), serde_json::to_vec_pretty(order)?)?;
+    Ok(())
+}
+
+// Mod folders in load order: listed ones first, then any new ones alphabetically
+fn discover(dir: &Path, order: &LoadOrder) -> Vec<String> {
+    let mut found: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    found.sort();
+    let mut ids: Vec<String> = order.order.iter().filter(|id| found.contains(id)).cloned().collect();
+    ids.extend(found.into_iter().filter(|id| !order.order.contains(id)));
+    ids
+}
+
+fn optional<T: for<'de> Deserialize<'de>>(dir: &Path, name: &str) -> Result<Option<T>> {
+    let path = dir.join(name);
+    if !path.exists() { return Ok(None); }
+    load_json(&path.to_string_lossy()).map(Some).with_context(|| format!("loading {}", path.display()))
+}
+
+// Everything one mod provides, parsed up front so a broken file skips the whole mod
+struct ModFiles {
+    recipes: Option<RecipesFile>,
+    research: Option<ResearchTree>,
+    potions: Option<PotionBook>,
+    events: Option<Vec<WorldEvent>>,
+    upgrades: Option<UpgradeBook>,
+    aspects: Option<AspectRegistry>,
+    icons: Vec<(String, PathBuf)>,
+}
+
+fn read_mod(dir: &Path) -> Result<ModFiles> {
+    let mut icons = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir.join("aspects")) {
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.extension().is_some_and(|x| x == "png")
+                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+            {
+                icons.push((stem.to_string(), path.clone()));
+            }
+        }
+    }
+    icons.sort();
+    Ok(ModFiles {
+        recipes: optional(dir, "recipes.json")?,
+        research: optional(dir, "research.json")?,
+        potions: optional(dir, "potions.json")?,
+        events: optional(dir, "events.json")?,
+        upgrades: optional(dir, "upgrades.json")?,
+        aspects: optional(dir, "aspects.json")?,
+        icons,
+    })
+}
+
+// Layer every enabled mod in `dir` over `data`
+pub fn apply_mods(data: &mut GameData, dir: &Path) {
+    let order = read_load_order(dir);
+    let mut report = ModReport { dir: Some(dir.to_path_buf()), ..Default::default() };
+    let mut owners = Owners::default();
+
+    for id in discover(dir, &order) {
+        let mod_dir = dir.join(&id);
+        let manifest: Manifest = match optional(&mod_dir, MANIFEST_FILE) {
+            Ok(m) => m.unwrap_or_default(),
+            Err(e) => {
+                report.errors.push(format!("{}: {:#}", id, e));
+                Manifest::default()
+            }
+        };
+        let enabled = !order.disabled.contains(&id);
+        report.mods.push(ModInfo {
+            id: id.clone(),
+            name: manifest.name.unwrap_or_else(|| id.clone()),
+            version: manifest.version.unwrap_or_default(),
+            description: manifest.description.unwrap_or_default(),
+            enabled,
+        });
+        if !enabled { continue; }
+
+        let files = match read_mod(&mod_dir) {
+            Ok(f) => f,
+            Err(e) => {
+                report.errors.push(format!("{}: {:#}", id, e));
+                continue;
+            }
+        };
+        if let Some(recipes) = files.recipes {
+            for (category, items) in recipes.crystals {
+                let target = data.recipes.crystals.entry(category).or_default();
+                for (item, cost) in items {
+                    owners.claim(&mut report, "recipe", &item, &id);
+                    target.insert(item, cost);
+                }
+            }
+        }
+        if let Some(research) = files.research {
+            for (tab, nodes) in research {
+                for node in nodes {
+                    owners.claim(&mut report, "research node", &node.id, &id);
+                    // A node keeps its slot when replaced within the same tab
+                    let existing = data.research.get_mut(&tab).and_then(|list| list.iter_mut().find(|n| n.id == node.id));
+                    if let Some(slot) = existing {
+                        *slot = node;
+                        continue;
+                    }
+                    for list in data.research.values_mut() {
+                        list.retain(|n| n.id != node.id);
+                    }
+                    data.research.entry(tab.clone()).or_default().push(node);
+                }
+            }
+        }
+        if let Some(potions) = files.potions {
+            for (pid, potion) in potions {
+                owners.claim(&mut report, "potion", &pid, &id);
+                data.potions.insert(pid, potion);
+            }
+        }
+        if let Some(events) = files.events {
+            for event in events {
+                owners.claim(&mut report, "event", &event.id, &id);
+                match data.world_events.iter_mut().find(|e| e.id == event.id) {
+                    Some(slot) => *slot = event,
+                    None => data.world_events.push(event),
+                }
+            }
+        }
+        if let Some(upgrades) = files.upgrades {
+            for (uid, spec) in upgrades {
+                owners.claim(&mut report, "upgrade", &uid, &id);
+                data.upgrades.insert(uid, spec);
+            }
+        }
+        if let Some(aspects) = files.aspects {
+            for (aid, info) in aspects {
+                owners.claim(&mut report, "aspect", &aid, &id);
+                data.aspects.insert(aid, info);
+            }
+        }
+        for (aspect, path) in files.icons {
+            owners.claim(&mut report, "icon", &aspect, &id);
+            data.icons.insert(aspect, path);
+        }
+    }
+    data.mods = report;
+}
+
+impl Clicker {
+    // Re-read the base data and mods, keeping the player's progress
+    pub fn reload_data(&mut self) -> Result<()> {
+        let data = GameData::load(&self.data_source)?;
+        self.install_data(data);
+        Ok(())
+    }
+
+    // Swap in freshly loaded data without touching progress
+    pub fn install_data(&mut self, data: GameData) {
+        let GameData { recipes, research, potions, world_events, upgrades, aspects, icons, mods } = data;
+        self.recipes = recipes;
+        self.research = research;
+        self.potions = potions;
+        self.world_events = world_events;
+        self.upgrades = upgrades;
+        self.aspects = aspects;
+        self.icon_overrides = icons;
+        self.mods = mods;
+        self.reveal_researched_aspects();
+        self.reveal_researched_nodes();
+        // Icons may have changed underneath the cache
+        self.textures.clear();
+    }
+
+    // Enable/disable or move a mod, persist the load order, and reload
+    pub fn change_mod(&mut self, id: &str, enabled: Option<bool>, shift: isize) -> Result<()> {
+        let Some(dir) = self.mods.dir.clone() else { return Ok(()); };
+        let mut ids: Vec<String> = self.mods.mods.iter().map(|m| m.id.clone()).collect();
+        let mut disabled: Vec<String> = self.mods.mods.iter().filter(|m| !m.enabled).map(|m| m.id.clone()).collect();
+        if let Some(on) = enabled {
+            disabled.retain(|d| d != id);
+            if !on { disabled.push(id.to_string()); }
+        }
+        if let Some(pos) = ids.iter().position(|m| m == id) {
+            let to = (pos as isize + shift).clamp(0, ids.len() as isize - 1) as usize;
+            let moved = ids.remove(pos);
+            ids.insert(to, moved);
+        }
+        write_load_order(&dir, &LoadOrder { order: ids, disabled })?;
+        self.reload_data()
+    }
+
+    pub fn show_mod_list(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new("Mods").strong().color(egui::Color32::LIGHT_BLUE));
+        if let Some(dir) = &self.mods.dir {
+            ui.label(egui::RichText::new(format!("Folder: {}", dir.display())).color(egui::Color32::LIGHT_GRAY));
+        }
+        if self.mods.mods.is_empty() {
+            ui.label(egui::RichText::new("No mods installed.").color(egui::Color32::LIGHT_GRAY));
+        }
+        let mut change: Option<(String, Option<bool>, isize)> = None;
+        let count = self.mods.mods.len();
+        for (i, m) in self.mods.mods.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let mut enabled = m.enabled;
+                if ui.checkbox(&mut enabled, "").changed() {
+                    change = Some((m.id.clone(), Some(enabled), 0));
+                }
+                if ui.add_enabled(i > 0, egui::Button::new("▲")).clicked() {
+                    change = Some((m.id.clone(), None, -1));
+                }
+                if ui.add_enabled(i + 1 < count, egui::Button::new("▼")).clicked() {
+                    change = Some((m.id.clone(), None, 1));
+                }
+                let title = if m.version.is_empty() { m.name.clone() } else { format!("{} {}", m.name, m.version) };
+                ui.label(egui::RichText::new(title).color(egui::Color32::WHITE));
+                if !m.description.is_empty() {
+                    ui.label(egui::RichText::new(&m.description).color(egui::Color32::LIGHT_GRAY));
+                }
+            });
+        }
+        for c in &self.mods.conflicts {
+            ui.label(egui::RichText::new(c).color(egui::Color32::from_rgb(230, 180, 90)));
+        }
+        for e in &self.mods.errors {
+            ui.label(egui::RichText::new(e).color(egui::Color32::LIGHT_RED));
+        }
+        if let Some((id, enabled, shift)) = change
+            && let Err(e) = self.change_mod(&id, enabled, shift)
+        {
+            self.mods.errors.push(format!("{:#}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    // A mods folder with the given mods, each a list of (file, contents)
+    fn mods_dir(name: &str, mods: &[(&str, &[(&str, &str)])]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("clicker-mods-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (id, files) in mods {
+            std::fs::create_dir_all(dir.join(id)).unwrap();
+            for (file, contents) in *files {
+                std::fs::write(dir.join(id).join(file), contents).unwrap();
+            }
+        }
+        dir
+    }
+
+    fn layered(dir: &Path) -> GameData {
+        let mut data = GameData::embedded().unwrap();
+        apply_mods(&mut data, dir);
+        let _ = std::fs::remove_dir_all(dir);
+        data
+    }
+
+    const LUX_2: &str = r#"{ "crystals": { "secondary": { "lux": { "aer": 2 } } } }"#;
+    const LUX_3: &str = r#"{ "crystals": { "secondary": { "lux": { "aer": 3 } } } }"#;
+
+    #[test]
+    fn later_mods_win_and_overrides_are_reported() {
+        let dir = mods_dir("order", &[
+            ("alpha", &[("recipes.json", LUX_2)]),
+            ("beta", &[("recipes.json", LUX_3)]),
+            ("gamma", &[]),
+        ]);
+        write_load_order(&dir, &LoadOrder { order: vec!["beta".to_string(), "alpha".to_string()], disabled: vec![] }).unwrap();
+        let data = layered(&dir);
+        // Listed mods keep their order; unlisted ones follow
+        let ids: Vec<&str> = data.mods.mods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["beta", "alpha", "gamma"]);
+        assert_eq!(data.recipes.crystals["secondary"]["lux"], IndexMap::from([("aer".to_string(), 2)]));
+        assert_eq!(data.mods.conflicts, vec!["recipe 'lux': alpha overrides beta"]);
+        assert!(data.mods.errors.is_empty());
+    }
+
+    #[test]
+    fn disabled_and_broken_mods_are_skipped() {
+        let dir = mods_dir("skip", &[
+            ("alpha", &[("recipes.json", LUX_2)]),
+            ("beta", &[("recipes.json", LUX_3)]),
+            ("broken", &[("potions.json", "{ nope")]),
+        ]);
+        write_load_order(&dir, &LoadOrder { order: vec![], disabled: vec!["beta".to_string()] }).unwrap();
+        let data = layered(&dir);
+        assert_eq!(data.recipes.crystals["secondary"]["lux"], IndexMap::from([("aer".to_string(), 2)]));
+        assert!(data.mods.conflicts.is_empty());
+        assert!(!data.mods.mods.iter().find(|m| m.id == "beta").unwrap().enabled);
+        assert_eq!(data.mods.errors.len(), 1);
+        assert!(data.mods.errors[0].starts_with("broken: loading"), "{}", data.mods.errors[0]);
+    }
+}
@@ -0,0 +1,197 @@
+// Where saves, backups and settings live. By default that is the platform's
+// per-user data/config directory; portable mode keeps everything next to the
+// executable the way older builds did.
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "clicker-game";
+// A file with this name next to the executable turns on portable mode
+const PORTABLE_MARKER: &str = "portable";
+// Launch-time backups of the save kept in the backups directory
+const MAX_BACKUPS: usize = 5;
+
+pub struct Paths {
+    pub data_dir: PathBuf,
+    pub config_dir: PathBuf,
+    pub portable: bool,
+}
+
+// Optional `settings.json` in the config dir; launch flags override it
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub data_dir: Option<PathBuf>,
+    pub windowed: Option<String>,
+    pub tui: bool,
+}
+
+fn exe_dir() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.to_path_buf()))
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+}
+
+// Absolute path from an environment variable; relative XDG values are ignored per the spec
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var).map(PathBuf::from).filter(|p| p.is_absolute())
+}
+
+fn home() -> Option<PathBuf> {
+    env_dir("HOME").or_else(|| env_dir("USERPROFILE"))
+}
+
+fn data_home() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| home().map(|h| h.join(".local/share")))
+    }
+}
+
+fn config_home() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| home().map(|h| h.join(".config")))
+    }
+}
+
+// Where builds before the data directories kept their save
+pub fn legacy_save_path() -> PathBuf {
+    exe_dir().join("save.json")
+}
+
+impl Paths {
+    pub fn resolve(portable: bool) -> Self {
+        let portable = portable || exe_dir().join(PORTABLE_MARKER).exists();
+        match (portable, data_home(), config_home()) {
+            (false, Some(data), Some(config)) => Self { data_dir: data.join(APP_DIR), config_dir: config.join(APP_DIR), portable },
+            // No usable home directory: fall back to the executable's folder
+            _ => Self { data_dir: exe_dir(), config_dir: exe_dir(), portable: true },
+        }
+    }
+
+    pub fn save_file(&self) -> PathBuf {
+        self.data_dir.join("save.json")
+    }
+
+    pub fn backup_dir(&self) -> PathBuf {
+        self.data_dir.join("backups")
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config_dir.join("settings.json")
+    }
+
+    pub fn load_config(&self) -> Result<Config> {
+        let path = self.config_file();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display())),
+            Err(_) => Ok(Config::default()),
+        }
+    }
+
+    // Move an exe-adjacent save from an older build into the data dir, once.
+    // The old file is renamed rather than deleted so a downgrade can still find it.
+    pub fn migrate_legacy_save(&self) -> Result<Option<PathBuf>> {
+        let legacy = legacy_save_path();
+        let target = self.save_file();
+        if self.portable || target.exists() || !legacy.exists() {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(&self.data_dir).with_context(|| format!("creating {}", self.data_dir.display()))?;
+        std::fs::copy(&legacy, &target).with_context(|| format!("copying {} to {}", legacy.display(), target.display()))?;
+        let _ = std::fs::rename(&legacy, legacy.with_extension("json.migrated"));
+        Ok(Some(legacy))
+    }
+}
+
+// Backup name prefix for one save: its file stem plus a hash of its full path,
+// so `--save` files with the same name in different folders keep separate backups
+fn backup_prefix(save: &Path) -> String {
+    let full = std::fs::canonicalize(save).unwrap_or_else(|_| save.to_path_buf());
+    // FNV-1a, which unlike std's hasher is stable across Rust versions
+    let hash = full.to_string_lossy().bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3));
+    let stem = save.file_stem().and_then(|s| s.to_str()).unwrap_or("save");
+    format!("{}-{:08x}-", stem, hash as u32)
+}
+
+// Copy `save` into `dir` under a timestamped name, dropping that save's oldest beyond MAX_BACKUPS
+pub fn backup_save(save: &Path, dir: &Path) -> Result<()> {
+    if !save.exists() { return Ok(()); }
+    std::fs::create_dir_all(dir)?;
+    let prefix = backup_prefix(save);
+    // Millisecond stamps, bumped past any backup already using one, so launches
+    // close together never overwrite each other
+    let mut stamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    while dir.join(format!("{}{}.json", prefix, stamp)).exists() {
+        stamp += 1;
+    }
+    std::fs::copy(save, dir.join(format!("{}{}.json", prefix, stamp)))?;
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".json")))
+        .collect();
+    // Names share a prefix and the stamps have the same digit count for centuries;
+    // older second stamps are shorter and sort first, as they are older
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in &backups[..excess] {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_made_together_are_all_kept() {
+        let root = std::env::temp_dir().join(format!("clicker-backup-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let save = root.join("save.json");
+        let dir = root.join("backups");
+        for i in 0..3 {
+            std::fs::write(&save, format!("{{\"n\":{}}}", i)).unwrap();
+            backup_save(&save, &dir).unwrap();
+        }
+        let mut kept: Vec<String> = std::fs::read_dir(&dir).unwrap().map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap()).collect();
+        kept.sort();
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(kept, vec!["{\"n\":0}", "{\"n\":1}", "{\"n\":2}"]);
+    }
+
+    #[test]
+    fn saves_with_the_same_name_keep_separate_backups() {
+        let root = std::env::temp_dir().join(format!("clicker-backup-key-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("backups");
+        let saves = [root.join("a").join("save.json"), root.join("b").join("save.json")];
+        for save in &saves {
+            std::fs::create_dir_all(save.parent().unwrap()).unwrap();
+            std::fs::write(save, "{}").unwrap();
+        }
+        // Fill the first save's backups past the limit, then back up the second once
+        for _ in 0..MAX_BACKUPS + 2 {
+            backup_save(&saves[0], &dir).unwrap();
+        }
+        backup_save(&saves[1], &dir).unwrap();
+        let count = |save: &Path| {
+            let prefix = backup_prefix(save);
+            std::fs::read_dir(&dir).unwrap().filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().starts_with(&prefix)).count()
+        };
+        let (first, second) = (count(&saves[0]), count(&saves[1]));
+        let _ = std::fs::remove_dir_all(&root);
+        assert_ne!(backup_prefix(&saves[0]), backup_prefix(&saves[1]));
+        assert_eq!((first, second), (MAX_BACKUPS, 1));
+    }
+}
@@ -0,0 +1,105 @@
+// Session recording and headless replay. A recording holds the save the session
+// started from (including the RNG stream) plus every command tagged with the
+// simulation tick it happened on.
+use crate::commands::GameCommand;
+use crate::{build_save, Clicker, GameData, Savefile, SIM_STEP};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedCommand {
+    pub tick: u64,
+    // Seconds since the session started, for humans reading the log
+    pub time: f32,
+    pub command: GameCommand,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recording {
+    pub start: Savefile,
+    pub commands: Vec<RecordedCommand>,
+    // Tick the session ended on, so idle time after the last command replays too
+    pub end_tick: u64,
+}
+
+pub struct Recorder {
+    pub path: std::path::PathBuf,
+    pub recording: Recording,
+}
+
+impl Recorder {
+    pub fn start(path: std::path::PathBuf, app: &Clicker) -> Self {
+        let recording = Recording { start: build_save(app), commands: Vec::new(), end_tick: app.sim_tick };
+        Self { path, recording }
+    }
+
+    pub fn record(&mut self, tick: u64, cmd: &GameCommand) {
+        self.recording.commands.push(RecordedCommand { tick, time: tick as f32 * SIM_STEP, command: cmd.clone() });
+    }
+
+    pub fn write(&mut self, end_tick: u64) -> Result<()> {
+        self.recording.end_tick = end_tick;
+        let json = serde_json::to_vec_pretty(&self.recording)?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+}
+
+// Load a recording, replay it from its start state and return the final state
+pub fn replay_file(path: &std::path::Path, data: GameData) -> Result<Savefile> {
+    let recording: Recording = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let mut app = Clicker::from_save_with_data(recording.start.clone(), data);
+    app.run_replay(&recording);
+    Ok(build_save(&app))
+}
+
+impl Clicker {
+    pub fn flush_recording(&mut self) {
+        let tick = self.sim_tick;
+        if let Some(rec) = &mut self.recorder {
+            let _ = rec.write(tick);
+        }
+    }
+
+    // Re-run a recording against `self` (freshly built from `recording.start`)
+    pub fn run_replay(&mut self, recording: &Recording) {
+        for entry in &recording.commands {
+            while self.sim_tick < entry.tick {
+                self.step();
+            }
+            self.apply(entry.command.clone());
+        }
+        while self.sim_tick < recording.end_tick {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+
+    #[test]
+    fn replaying_a_recording_reproduces_the_final_state() {
+        let path = std::env::temp_dir().join(format!("clicker-replay-test-{}.json", std::process::id()));
+        let mut app = test_clicker(test_save(5));
+        app.recorder = Some(Recorder::start(path.clone(), &app));
+        // Commands spread over simulation time so timers and RNG rolls interleave
+        for i in 0..200 {
+            app.apply(GameCommand::Gather);
+            if i % 50 == 49 {
+                app.apply(GameCommand::Unlock { node: "shard_husbandry".to_string() });
+            }
+            for _ in 0..5 {
+                app.step();
+            }
+        }
+        app.flush_recording();
+        let expected = serde_json::to_value(build_save(&app)).unwrap();
+
+        let replayed = replay_file(&path, GameData::embedded().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(serde_json::to_value(replayed).unwrap(), expected);
+    }
+}
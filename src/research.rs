@@ -0,0 +1,295 @@
+// The research tree as the player finds it: a node stays hidden until a researched
+// node lists it in `unlocks_nodes`, nodes no other node lists are visible from the
+// start, and hidden nodes next to a visible one show as "?" placeholders.
+use crate::{Clicker, ResearchNode, ResearchTree};
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+
+// Seconds a newly revealed node takes to fade and grow in
+pub const REVEAL_ANIM_SECS: f32 = 0.8;
+
+// Most search results listed under the search box
+pub const MAX_SEARCH_RESULTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeVisibility {
+    Visible,
+    // Hidden, but researching a visible node would reveal it
+    Placeholder,
+    Hidden,
+}
+
+// Research filter toggles; with none on, nothing is dimmed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResearchFilter {
+    pub affordable: bool,
+    pub locked: bool,
+    pub completed: bool,
+}
+
+impl ResearchFilter {
+    pub fn active(&self) -> bool {
+        self.affordable || self.locked || self.completed
+    }
+}
+
+// A node found by the research search
+pub struct SearchHit {
+    pub tab: String,
+    pub id: String,
+    pub name: String,
+}
+
+impl Clicker {
+    // Every node id some node reveals; the rest need no revealing
+    fn revealable_nodes(&self) -> HashSet<&str> {
+        self.research
+            .values()
+            .flatten()
+            .flat_map(|n| n.unlocks_nodes.iter().flatten())
+            .map(String::as_str)
+            .collect()
+    }
+
+    // Visibility of every node in the tree, computed together since placeholders
+    // depend on which of their revealers are visible
+    pub fn research_visibility(&self) -> HashMap<String, NodeVisibility> {
+        let revealable = self.revealable_nodes();
+        let shown = |id: &str| !revealable.contains(id) || self.revealed_nodes.contains(id) || self.unlocked_nodes.contains(id);
+        let mut visibility: HashMap<String, NodeVisibility> = self
+            .research
+            .values()
+            .flatten()
+            .map(|n| (n.id.clone(), if shown(&n.id) { NodeVisibility::Visible } else { NodeVisibility::Hidden }))
+            .collect();
+        for n in self.research.values().flatten().filter(|n| shown(&n.id)) {
+            for next in n.unlocks_nodes.iter().flatten() {
+                if let Some(v @ NodeVisibility::Hidden) = visibility.get_mut(next) {
+                    *v = NodeVisibility::Placeholder;
+                }
+            }
+        }
+        visibility
+    }
+
+    // Reveal nodes after a research, animating the ones seen for the first time
+    pub fn reveal_nodes(&mut self, ids: &[String]) {
+        for id in ids {
+            if self.revealed_nodes.insert(id.clone()) {
+                self.reveal_anims.insert(id.clone(), REVEAL_ANIM_SECS);
+            }
+        }
+    }
+
+    // Apply the reveals of nodes already researched (the default save's starting
+    // nodes, or reveals added to the data later) without animating them
+    pub fn reveal_researched_nodes(&mut self) {
+        let revealed: Vec<String> = self
+            .research
+            .values()
+            .flatten()
+            .filter(|n| self.unlocked_nodes.contains(&n.id))
+            .flat_map(|n| n.unlocks_nodes.iter().flatten().cloned())
+            .collect();
+        self.revealed_nodes.extend(revealed);
+    }
+
+    // Progress of a node's reveal animation, 0.0 (just revealed) to 1.0 (done)
+    pub fn reveal_progress(&self, id: &str) -> f32 {
+        self.reveal_anims.get(id).map(|left| 1.0 - left / REVEAL_ANIM_SECS).unwrap_or(1.0)
+    }
+
+    pub fn tick_reveal_anims(&mut self, dt: f32) {
+        for left in self.reveal_anims.values_mut() {
+            *left -= dt;
+        }
+        self.reveal_anims.retain(|_, left| *left > 0.0);
+    }
+}
+
+impl Clicker {
+    // Case-insensitive match on name, description, cost aspects and unlock effects
+    pub fn node_matches_query(&self, node: &ResearchNode, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() { return true; }
+        let costs = node.cost.keys().flat_map(|k| [k.clone(), self.aspect_name(k)]);
+        let effects = node.unlocks.iter().flatten().cloned().chain(node.unlocks_menu.clone());
+        [node.name.clone(), node.description.clone()]
+            .into_iter()
+            .chain(costs)
+            .chain(effects)
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    pub fn node_matches_filter(&self, node: &ResearchNode) -> bool {
+        let filter = self.research_filter;
+        if !filter.active() { return true; }
+        let unlocked = self.unlocked_nodes.contains(&node.id);
+        let affordable = !unlocked && self.can_unlock_node(&node.id) && self.can_afford_cost(&node.cost);
+        (filter.affordable && affordable) || (filter.locked && !unlocked) || (filter.completed && unlocked)
+    }
+
+    // Visible nodes in open tabs matching the search box
+    pub fn search_research(&self, query: &str) -> Vec<SearchHit> {
+        if query.trim().is_empty() { return Vec::new(); }
+        let visibility = self.research_visibility();
+        self.research
+            .iter()
+            .filter(|(tab, _)| self.unlocked_research_tabs.contains(*tab))
+            .flat_map(|(tab, nodes)| nodes.iter().map(move |n| (tab, n)))
+            .filter(|(_, n)| visibility.get(&n.id) == Some(&NodeVisibility::Visible))
+            .filter(|(_, n)| self.node_matches_query(n, query))
+            .map(|(tab, n)| SearchHit { tab: tab.clone(), id: n.id.clone(), name: n.name.clone() })
+            .collect()
+    }
+
+    pub fn show_research_search(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Search:").color(egui::Color32::WHITE));
+            ui.add(egui::TextEdit::singleline(&mut self.research_search).hint_text("name, aspect or effect").desired_width(200.0));
+            ui.separator();
+            ui.toggle_value(&mut self.research_filter.affordable, "Affordable");
+            ui.toggle_value(&mut self.research_filter.locked, "Locked");
+            ui.toggle_value(&mut self.research_filter.completed, "Completed");
+        });
+        let hits = self.search_research(&self.research_search);
+        if self.research_search.trim().is_empty() { return; }
+        let mut jump: Option<String> = None;
+        ui.horizontal_wrapped(|ui| {
+            if hits.is_empty() {
+                ui.label(egui::RichText::new("No matching research").color(egui::Color32::LIGHT_GRAY));
+            }
+            for hit in hits.iter().take(MAX_SEARCH_RESULTS) {
+                if ui.button(format!("{} ({})", hit.name, hit.tab)).clicked() {
+                    jump = Some(hit.id.clone());
+                }
+            }
+            if hits.len() > MAX_SEARCH_RESULTS {
+                ui.label(egui::RichText::new(format!("+{} more", hits.len() - MAX_SEARCH_RESULTS)).color(egui::Color32::LIGHT_GRAY));
+            }
+        });
+        if let Some(id) = jump { self.jump_to_node(&id); }
+    }
+}
+
+impl Clicker {
+    pub fn node_tab(&self, id: &str) -> Option<&str> {
+        self.research.iter().find(|(_, nodes)| nodes.iter().any(|n| n.id == id)).map(|(tab, _)| tab.as_str())
+    }
+
+    // (tab, node) for each of `node`'s prerequisites that lives outside `tab`
+    pub fn cross_tab_prerequisites(&self, tab: &str, node: &ResearchNode) -> Vec<(String, &ResearchNode)> {
+        node.prerequisites
+            .iter()
+            .filter_map(|pre| {
+                self.research
+                    .iter()
+                    .filter(|(t, _)| t.as_str() != tab)
+                    .find_map(|(t, nodes)| nodes.iter().find(|n| &n.id == pre).map(|n| (t.clone(), n)))
+            })
+            .collect()
+    }
+}
+
+// Problems in how the tree hangs together: prerequisite cycles (within or across
+// tabs) and tabs no node ever opens
+pub fn structure_problems(tree: &ResearchTree) -> Vec<String> {
+    let mut problems = Vec::new();
+    let nodes: HashMap<&str, &ResearchNode> = tree.values().flatten().map(|n| (n.id.as_str(), n)).collect();
+
+    fn visit<'a>(id: &'a str, nodes: &HashMap<&'a str, &'a ResearchNode>, path: &mut Vec<&'a str>, done: &mut HashSet<&'a str>, problems: &mut Vec<String>) {
+        if done.contains(id) { return; }
+        if let Some(start) = path.iter().position(|p| *p == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id);
+            problems.push(format!("research.json: prerequisite cycle {}", cycle.join(" -> ")));
+            return;
+        }
+        let Some(node) = nodes.get(id) else { return; };
+        path.push(id);
+        for pre in &node.prerequisites {
+            visit(pre.as_str(), nodes, path, done, problems);
+        }
+        path.pop();
+        done.insert(id);
+    }
+    let mut done = HashSet::new();
+    for n in tree.values().flatten() {
+        visit(n.id.as_str(), &nodes, &mut Vec::new(), &mut done, &mut problems);
+    }
+
+    // The first tab is open from the start; the rest need an `unlocks_menu`
+    let opened: HashSet<&str> = tree.values().flatten().filter_map(|n| n.unlocks_menu.as_deref()).collect();
+    for tab in tree.keys().skip(1).filter(|t| !opened.contains(t.as_str())) {
+        problems.push(format!("research.json: tab '{}' is never opened by any node's unlocks_menu", tab));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save};
+    use indexmap::IndexMap;
+
+    fn node(id: &str, prerequisites: &[&str], reveals: &[&str]) -> ResearchNode {
+        ResearchNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            x: Some(0.0),
+            y: Some(0.0),
+            cost: IndexMap::new(),
+            research_time: None,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks: None,
+            unlocks_nodes: Some(reveals.iter().map(|r| r.to_string()).collect()),
+            unlocks_menu: None,
+        }
+    }
+
+    // "Home": a reveals b, b reveals c; "Away": x needs a, y stands alone
+    fn app() -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        let mut tree = ResearchTree::new();
+        tree.insert("Home".to_string(), vec![node("a", &[], &["b"]), node("b", &["a"], &["c"]), node("c", &["b"], &[])]);
+        tree.insert("Away".to_string(), vec![node("x", &["a"], &[]), node("y", &[], &[])]);
+        app.research = tree;
+        app.unlocked_nodes.clear();
+        app.revealed_nodes.clear();
+        app
+    }
+
+    fn visibility_of(app: &Clicker, ids: &[&str]) -> Vec<NodeVisibility> {
+        let visibility = app.research_visibility();
+        ids.iter().map(|id| visibility[*id]).collect()
+    }
+
+    #[test]
+    fn hidden_nodes_next_to_visible_ones_are_placeholders() {
+        use NodeVisibility::*;
+        let mut app = app();
+        assert_eq!(visibility_of(&app, &["a", "b", "c", "x", "y"]), vec![Visible, Placeholder, Hidden, Visible, Visible]);
+
+        app.unlocked_nodes.insert("a".to_string());
+        app.reveal_researched_nodes();
+        assert_eq!(visibility_of(&app, &["b", "c"]), vec![Visible, Placeholder]);
+    }
+
+    #[test]
+    fn researched_nodes_stay_visible_without_a_reveal() {
+        let mut app = app();
+        app.unlocked_nodes.insert("c".to_string());
+        assert_eq!(visibility_of(&app, &["b", "c"]), vec![NodeVisibility::Placeholder, NodeVisibility::Visible]);
+    }
+
+    #[test]
+    fn cross_tab_prerequisites_name_the_other_tab() {
+        let app = app();
+        let x = app.research_node("x").unwrap();
+        let links: Vec<(String, String)> = app.cross_tab_prerequisites("Away", x).into_iter().map(|(t, n)| (t, n.id.clone())).collect();
+        assert_eq!(links, vec![("Home".to_string(), "a".to_string())]);
+        let b = app.research_node("b").unwrap();
+        assert!(app.cross_tab_prerequisites("Home", b).is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+// Research canvas camera: one per research tab, with zoom-to-cursor, bounds taken
+// from the tab's node positions, "fit all", eased jumps and the corner minimap.
+use crate::research::NodeVisibility;
+use crate::{Clicker, ResearchNode};
+use eframe::egui;
+use std::collections::HashMap;
+
+// Size of a research node at zoom 1.0
+pub const NODE_SIZE: egui::Vec2 = egui::vec2(180.0, 64.0);
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 3.0;
+// "Fit all" never zooms in further than this
+const FIT_MAX_ZOOM: f32 = 1.5;
+// Zoom the camera settles on when jumping to a node
+const JUMP_ZOOM: f32 = 1.0;
+// Space kept around the nodes, in canvas units
+const BOUNDS_MARGIN: f32 = 60.0;
+// How quickly the camera closes in on its target (per second)
+const CAMERA_EASE: f32 = 8.0;
+const MINIMAP_SIZE: egui::Vec2 = egui::vec2(200.0, 130.0);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResearchCamera {
+    // Screen offset of the canvas origin from the top-left of the view
+    pub offset: egui::Vec2,
+    pub zoom: f32,
+    // Offset/zoom being eased toward after a jump or "fit all"
+    target: Option<(egui::Vec2, f32)>,
+}
+
+impl Default for ResearchCamera {
+    fn default() -> Self {
+        Self { offset: egui::Vec2::ZERO, zoom: 1.0, target: None }
+    }
+}
+
+impl ResearchCamera {
+    pub fn to_screen(self, view: egui::Rect, p: egui::Pos2) -> egui::Pos2 {
+        view.min + self.offset + p.to_vec2() * self.zoom
+    }
+
+    pub fn to_canvas(self, view: egui::Rect, screen: egui::Pos2) -> egui::Pos2 {
+        ((screen - view.min - self.offset) / self.zoom).to_pos2()
+    }
+
+    pub fn animating(&self) -> bool {
+        self.target.is_some()
+    }
+
+    // Zoom by `factor` keeping the canvas point under `anchor` (a screen position) fixed
+    pub fn zoom_at(&mut self, view: egui::Rect, anchor: egui::Pos2, factor: f32) {
+        let under = self.to_canvas(view, anchor);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.offset = anchor - view.min - under.to_vec2() * self.zoom;
+        self.target = None;
+    }
+
+    pub fn pan(&mut self, delta: egui::Vec2) {
+        self.offset += delta;
+        self.target = None;
+    }
+
+    // Ease toward showing `p` at the centre of a `view_size` view at `zoom`
+    pub fn center_on(&mut self, view_size: egui::Vec2, p: egui::Pos2, zoom: f32) {
+        self.target = Some((view_size * 0.5 - p.to_vec2() * zoom, zoom));
+    }
+
+    pub fn fit(&mut self, view_size: egui::Vec2, bounds: egui::Rect) {
+        let zoom = (view_size.x / bounds.width()).min(view_size.y / bounds.height()).clamp(MIN_ZOOM, FIT_MAX_ZOOM);
+        self.center_on(view_size, bounds.center(), zoom);
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let Some((offset, zoom)) = self.target else { return; };
+        let t = 1.0 - (-CAMERA_EASE * dt).exp();
+        self.offset += (offset - self.offset) * t;
+        self.zoom += (zoom - self.zoom) * t;
+        if (offset - self.offset).length() < 0.5 && (zoom - self.zoom).abs() < 0.001 {
+            self.offset = offset;
+            self.zoom = zoom;
+            self.target = None;
+        }
+    }
+
+    // Keep the node area from being dragged out of sight
+    pub fn clamp(&mut self, view_size: egui::Vec2, bounds: egui::Rect) {
+        if self.target.is_some() { return; }
+        let range = |view: f32, min: f32, max: f32| {
+            let (a, b) = (view - max * self.zoom, -min * self.zoom);
+            (a.min(b), a.max(b))
+        };
+        let (lo, hi) = range(view_size.x, bounds.min.x, bounds.max.x);
+        self.offset.x = self.offset.x.clamp(lo, hi);
+        let (lo, hi) = range(view_size.y, bounds.min.y, bounds.max.y);
+        self.offset.y = self.offset.y.clamp(lo, hi);
+    }
+}
+
+// Canvas area covered by `nodes`, including the nodes' own size and a margin
+pub fn tab_bounds<'a>(nodes: impl IntoIterator<Item = &'a ResearchNode>) -> egui::Rect {
+    let mut bounds = egui::Rect::NOTHING;
+    for n in nodes {
+        bounds = bounds.union(egui::Rect::from_center_size(n.pos(), NODE_SIZE));
+    }
+    if !bounds.is_positive() {
+        return egui::Rect::from_min_size(egui::Pos2::ZERO, NODE_SIZE).expand(BOUNDS_MARGIN);
+    }
+    bounds.expand(BOUNDS_MARGIN)
+}
+
+// Where the minimap sits inside the canvas
+pub fn minimap_rect(view: egui::Rect) -> egui::Rect {
+    egui::Rect::from_min_size(view.max - MINIMAP_SIZE - egui::vec2(10.0, 10.0), MINIMAP_SIZE)
+}
+
+impl Clicker {
+    // The tab's camera, starting with everything in view
+    pub fn research_camera(&mut self, tab: &str) -> &mut ResearchCamera {
+        if !self.research_cameras.contains_key(tab) {
+            let mut camera = ResearchCamera::default();
+            if let Some(nodes) = self.research.get(tab) {
+                camera.fit(self.research_view_size, tab_bounds(nodes));
+                if let Some((offset, zoom)) = camera.target.take() {
+                    camera.offset = offset;
+                    camera.zoom = zoom;
+                }
+            }
+            self.research_cameras.insert(tab.to_string(), camera);
+        }
+        self.research_cameras.get_mut(tab).expect("camera inserted above")
+    }
+
+    // Switch to the node's tab and start panning its camera to centre it
+    pub fn jump_to_node(&mut self, id: &str) {
+        let Some((tab, pos)) = self
+            .research
+            .iter()
+            .find_map(|(tab, nodes)| nodes.iter().find(|n| n.id == id).map(|n| (tab.clone(), n.pos())))
+        else {
+            return;
+        };
+        let view_size = self.research_view_size;
+        self.research_camera(&tab).center_on(view_size, pos, JUMP_ZOOM);
+        self.current_research_tab = tab;
+    }
+
+    pub fn fit_research_view(&mut self) {
+        let tab = self.current_research_tab.clone();
+        let Some(nodes) = self.research.get(&tab) else { return; };
+        let (bounds, view_size) = (tab_bounds(nodes), self.research_view_size);
+        self.research_camera(&tab).fit(view_size, bounds);
+    }
+
+    // Corner overview of the tab: node states, the visible area, and click/drag to move there
+    pub fn show_research_minimap(
+        &mut self,
+        ui: &mut egui::Ui,
+        view: egui::Rect,
+        bounds: egui::Rect,
+        visibility: &HashMap<String, NodeVisibility>,
+    ) {
+        let tab = self.current_research_tab.clone();
+        let Some(nodes) = self.research.get(&tab) else { return; };
+        let map = minimap_rect(view);
+        let scale = (map.width() / bounds.width()).min(map.height() / bounds.height());
+        let inner = egui::Rect::from_center_size(map.center(), bounds.size() * scale);
+        let to_map = |p: egui::Pos2| inner.min + (p - bounds.min) * scale;
+
+        let painter = ui.painter_at(view);
+        painter.rect_filled(map, egui::Rounding::same(4), egui::Color32::from_black_alpha(200));
+        painter.rect_stroke(map, egui::Rounding::same(4), egui::Stroke::new(1.0, egui::Color32::from_gray(90)), egui::StrokeKind::Outside);
+        for n in nodes {
+            let color = match visibility.get(&n.id) {
+                Some(NodeVisibility::Visible) if self.unlocked_nodes.contains(&n.id) => egui::Color32::from_rgb(50, 190, 90),
+                Some(NodeVisibility::Visible) if self.can_unlock_node(&n.id) && self.can_afford_cost(&n.cost) => egui::Color32::from_rgb(60, 140, 220),
+                Some(NodeVisibility::Visible) => egui::Color32::from_gray(120),
+                Some(NodeVisibility::Placeholder) => egui::Color32::from_gray(55),
+                _ => continue,
+            };
+            let size = (NODE_SIZE * scale).max(egui::vec2(3.0, 2.0));
+            painter.rect_filled(egui::Rect::from_center_size(to_map(n.pos()), size), egui::Rounding::ZERO, color);
+        }
+        let camera = *self.research_camera(&tab);
+        let seen = egui::Rect::from_min_max(to_map(camera.to_canvas(view, view.min)), to_map(camera.to_canvas(view, view.max)));
+        painter.rect_stroke(seen.intersect(map), egui::Rounding::ZERO, egui::Stroke::new(1.0, egui::Color32::WHITE), egui::StrokeKind::Inside);
+
+        let response = ui.interact(map, ui.id().with("research_minimap"), egui::Sense::click_and_drag());
+        if let Some(pos) = response.interact_pointer_pos().filter(|_| response.clicked() || response.dragged()) {
+            let target = bounds.min + (pos - inner.min) / scale;
+            let camera = self.research_camera(&tab);
+            camera.offset = view.size() * 0.5 - target.to_vec2() * camera.zoom;
+            camera.target = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view() -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(800.0, 600.0))
+    }
+
+    fn close(a: egui::Pos2, b: egui::Pos2) -> bool {
+        (a - b).length() < 1e-3
+    }
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let mut camera = ResearchCamera { offset: egui::vec2(-40.0, 25.0), zoom: 1.3, target: None };
+        let cursor = egui::pos2(420.0, 310.0);
+        let under = camera.to_canvas(view(), cursor);
+        for factor in [1.25, 0.5, 10.0, 0.01] {
+            camera.zoom_at(view(), cursor, factor);
+            assert!(close(camera.to_screen(view(), under), cursor), "moved at zoom {}", camera.zoom);
+        }
+        // The last two factors ran into the limits
+        assert_eq!(camera.zoom, MIN_ZOOM);
+    }
+
+    #[test]
+    fn clamp_keeps_the_nodes_in_view() {
+        let bounds = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(2000.0, 300.0));
+        let size = view().size();
+        // Wider than the view: an edge may not come inside it
+        let mut camera = ResearchCamera { offset: egui::vec2(500.0, 0.0), ..Default::default() };
+        camera.clamp(size, bounds);
+        assert_eq!(camera.offset.x, 0.0);
+        camera.offset = egui::vec2(-5000.0, 0.0);
+        camera.clamp(size, bounds);
+        assert_eq!(camera.offset.x, size.x - 2000.0);
+
+        // Shorter than the view: the nodes may not leave it
+        camera.offset = egui::vec2(0.0, 900.0);
+        camera.clamp(size, bounds);
+        assert_eq!(camera.offset.y, size.y - 300.0);
+        camera.offset = egui::vec2(0.0, -10.0);
+        camera.clamp(size, bounds);
+        assert_eq!(camera.offset.y, 0.0);
+    }
+
+    #[test]
+    fn clamp_waits_for_an_eased_jump() {
+        let bounds = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(100.0, 100.0));
+        let mut camera = ResearchCamera::default();
+        camera.center_on(view().size(), egui::pos2(5000.0, 5000.0), 1.0);
+        camera.offset = egui::vec2(-9000.0, -9000.0);
+        camera.clamp(view().size(), bounds);
+        assert_eq!(camera.offset, egui::vec2(-9000.0, -9000.0));
+    }
+}
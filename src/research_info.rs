@@ -0,0 +1,271 @@
+// What a research node costs and grants, in words: the hover tooltip on the
+// canvas, the effect lines, and the messages shown after researching or queueing.
+use crate::alchemy::format_countdown;
+use crate::research::NodeVisibility;
+use crate::{Clicker, ResearchNode, UnlockError, UnlockOutcome};
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+
+impl Clicker {
+    pub fn research_node(&self, id: &str) -> Option<&ResearchNode> {
+        self.research.values().flatten().find(|n| n.id == id)
+    }
+
+    // Name of a node for messages, with its own tab when that differs from `seen_from`
+    fn node_label(&self, id: &str, seen_from: Option<&str>) -> String {
+        let name = self.research_node(id).map_or(id.to_string(), |n| n.name.clone());
+        match self.node_tab(id).filter(|t| seen_from.is_some_and(|from| from != *t)) {
+            Some(other) => format!("{} ({})", name, other),
+            None => name,
+        }
+    }
+
+    // `node_label`, or "???" for a node the player hasn't seen yet
+    pub fn visible_node_label(&self, id: &str, seen_from: Option<&str>, visibility: &HashMap<String, NodeVisibility>) -> String {
+        match visibility.get(id) {
+            Some(NodeVisibility::Visible) => self.node_label(id, seen_from),
+            _ => "???".to_string(),
+        }
+    }
+
+    // What researching a node does, one line per effect
+    pub fn node_effects(&self, node: &ResearchNode) -> Vec<String> {
+        let mut effects: Vec<String> = node.unlocks.iter().flatten().map(|u| self.effect_text(u)).collect();
+        if let Some(tab) = &node.unlocks_menu {
+            effects.push(format!("Opens the {} tab", tab));
+        }
+        if let Some(nodes) = node.unlocks_nodes.as_ref().filter(|n| !n.is_empty()) {
+            effects.push(format!("Reveals {} new research", nodes.len()));
+        }
+        effects
+    }
+
+    fn effect_text(&self, unlock: &str) -> String {
+        if let Some(item) = unlock.strip_prefix("recipe:") {
+            return format!("Recipe: {}", self.aspect_name(item));
+        }
+        if let Some(aspect) = unlock.strip_prefix("aspect:") {
+            return match self.aspect_discovered(aspect) {
+                true => format!("Reveals the {} aspect", self.aspect_name(aspect)),
+                false => "Reveals a new aspect".to_string(),
+            };
+        }
+        match unlock {
+            "secondary_crystals" => "Secondary crystal recipes",
+            "tertiary_crystals" => "Tertiary crystal recipes",
+            "quaternary_crystals" => "Quaternary crystal recipes",
+            "vis_conversion" => "Vis conversion",
+            "auto_clicking" => "Auto-clicking",
+            "advancedRunes" => "Advanced runes",
+            "vis_coagulator" => "Vis coagulator",
+            "transmutation" => "Transmutation",
+            "alchemy" => "Alchemy",
+            "crucible" => "The crucible",
+            "flux_wards" => "Flux wards",
+            other => other,
+        }
+        .to_string()
+    }
+
+    // Message shown after trying to research `id`, naming whatever is missing
+    pub fn unlock_message(&self, id: &str, result: Result<UnlockOutcome, UnlockError>) -> Result<String, String> {
+        let tab = self.node_tab(id);
+        let name = self.node_label(id, None);
+        match result {
+            Ok(UnlockOutcome::Started) => Ok(format!("Started researching {} ({})", name, format_countdown(self.research_time(id)))),
+            Ok(_) => Ok(format!("Researched {}", name)),
+            Err(UnlockError::NotFound) => Err(format!("There is no research called '{}'", id)),
+            Err(UnlockError::AlreadyUnlocked) => Err(format!("{} is already researched", name)),
+            Err(UnlockError::InProgress) => Err(format!("{} is being researched", name)),
+            Err(UnlockError::AlreadyQueued) => Err(format!("{} is already queued", name)),
+            Err(UnlockError::Busy(other)) => Err(format!("Can't research {}: {} is still being researched", name, self.node_label(&other, None))),
+            Err(UnlockError::TabLocked(tab)) => Err(format!("Can't research {}: the {} tab isn't open yet", name, tab)),
+            Err(UnlockError::PrerequisitesMissing(missing)) => {
+                let visibility = self.research_visibility();
+                let missing: Vec<String> = missing.iter().map(|pre| self.visible_node_label(pre, tab, &visibility)).collect();
+                Err(format!("Can't research {}: research {} first", name, missing.join(", ")))
+            }
+            Err(UnlockError::Insufficient(short)) => {
+                let short: Vec<String> = short
+                    .iter()
+                    .map(|s| format!("{} more {} (have {})", s.needed - s.have, self.aspect_name(&s.resource), s.have))
+                    .collect();
+                Err(format!("Can't research {}: needs {}", name, short.join(", ")))
+            }
+        }
+    }
+
+    // "Queued X", naming any prerequisites queued ahead of it
+    pub fn queued_message(&self, queued: &[String]) -> String {
+        let names: Vec<String> = queued.iter().map(|id| self.node_label(id, None)).collect();
+        match names.split_last() {
+            Some((last, [])) => format!("Queued {}", last),
+            Some((last, before)) => format!("Queued {} after {}", last, before.join(", ")),
+            None => String::new(),
+        }
+    }
+
+    pub fn show_research_message(&self, ui: &mut egui::Ui) {
+        let (text, color) = match &self.research_message {
+            Ok(text) => (text, egui::Color32::from_rgb(120, 220, 140)),
+            Err(text) => (text, egui::Color32::from_rgb(240, 110, 100)),
+        };
+        if !text.is_empty() {
+            ui.label(egui::RichText::new(text).color(color));
+        }
+    }
+
+    // Icon already loaded by `get_crystal_icon`, for drawing from `&self`
+    fn cached_icon(&self, id: &str) -> Option<egui::TextureId> {
+        self.textures.get(id).map(|t| t.id())
+    }
+
+    // Load the icons of every cost in `tab` so tooltips can draw them
+    pub fn load_cost_icons(&mut self, ctx: &egui::Context, tab: &str) {
+        let ids: HashSet<String> = self
+            .research
+            .get(tab)
+            .into_iter()
+            .flatten()
+            .flat_map(|n| n.cost.keys())
+            .filter(|k| !matches!(k.as_str(), "Vis" | "Soul" | "Souls"))
+            .cloned()
+            .collect();
+        for id in ids {
+            self.get_crystal_icon(ctx, &id);
+        }
+    }
+
+    // Tooltip body for a visible node: each cost against what the player has,
+    // prerequisites with their status, and what researching it grants
+    pub fn show_node_tooltip(&self, ui: &mut egui::Ui, tab: &str, node: &ResearchNode) {
+        let (red, green) = (egui::Color32::from_rgb(240, 110, 100), egui::Color32::from_rgb(120, 220, 140));
+        let unlocked = self.unlocked_nodes.contains(&node.id);
+        ui.label(egui::RichText::new(&node.name).strong().color(egui::Color32::WHITE));
+        ui.label(&node.description);
+
+        let costs: Vec<(&String, &u32)> = node.cost.iter().filter(|(k, _)| !matches!(k.as_str(), "Soul" | "Souls")).collect();
+        if !costs.is_empty() {
+            ui.separator();
+            ui.label(egui::RichText::new("Cost").strong().color(egui::Color32::LIGHT_BLUE));
+            for (k, &needed) in costs {
+                let have = self.cost_have(k);
+                ui.horizontal(|ui| {
+                    match self.cached_icon(k) {
+                        Some(tex) => { ui.add(egui::Image::new((tex, egui::vec2(16.0, 16.0))).tint(self.icon_tint(k))); }
+                        None => { ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover()); }
+                    }
+                    ui.label(self.aspect_text(k));
+                    ui.label(egui::RichText::new(format!("{} / {}", have, needed)).color(egui::Color32::LIGHT_GRAY));
+                    if unlocked {
+                        return;
+                    }
+                    if have >= needed {
+                        ui.label(egui::RichText::new("✔").color(green));
+                    } else {
+                        ui.label(egui::RichText::new(format!("(-{})", needed - have)).color(red));
+                    }
+                });
+            }
+        }
+
+        if !node.prerequisites.is_empty() {
+            ui.separator();
+            ui.label(egui::RichText::new("Requires").strong().color(egui::Color32::LIGHT_BLUE));
+            let visibility = self.research_visibility();
+            for pre in &node.prerequisites {
+                let done = self.unlocked_nodes.contains(pre);
+                let label = self.visible_node_label(pre, Some(tab), &visibility);
+                let (mark, color) = if done { ("✔", green) } else { ("✖", red) };
+                ui.label(egui::RichText::new(format!("{} {}", mark, label)).color(color));
+            }
+        }
+
+        let effects = self.node_effects(node);
+        if !effects.is_empty() {
+            ui.separator();
+            ui.label(egui::RichText::new("Grants").strong().color(egui::Color32::LIGHT_BLUE));
+            for effect in effects {
+                ui.label(format!("• {}", effect));
+            }
+        }
+
+        ui.separator();
+        if let Some(time) = node.research_time.filter(|t| *t > 0.0 && !unlocked) {
+            ui.label(egui::RichText::new(format!("Research time: {}", format_countdown(time))).color(egui::Color32::LIGHT_GRAY));
+        }
+        let idle = self.researching.is_none() && self.research_queue.is_empty();
+        if unlocked {
+            ui.label(egui::RichText::new("Researched").color(green));
+        } else if let Some(job) = self.researching.as_ref().filter(|job| job.node == node.id) {
+            ui.label(egui::RichText::new(format!("Researching ({} left)", format_countdown(job.remaining))).color(green));
+        } else if let Some(i) = self.queued_position(&node.id) {
+            ui.label(egui::RichText::new(format!("Queued (#{})", i + 1)).color(egui::Color32::LIGHT_GRAY));
+        } else if idle && self.can_unlock_node(&node.id) && self.can_afford_cost(&node.cost) {
+            ui.label(egui::RichText::new("Click to research").color(green));
+        } else {
+            ui.label(egui::RichText::new("Click to queue it with its prerequisites").color(egui::Color32::LIGHT_GRAY));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save, ResearchTree};
+    use indexmap::IndexMap;
+
+    fn node(id: &str, name: &str, prerequisites: &[&str], reveals: &[&str]) -> ResearchNode {
+        ResearchNode {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            x: Some(0.0),
+            y: Some(0.0),
+            cost: IndexMap::new(),
+            research_time: None,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks: None,
+            unlocks_nodes: Some(reveals.iter().map(|r| r.to_string()).collect()),
+            unlocks_menu: None,
+        }
+    }
+
+    // "Home": Alpha reveals Beta, which Gamma needs; "Away": Xeno needs Alpha
+    fn app() -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        let mut tree = ResearchTree::new();
+        tree.insert("Home".to_string(), vec![node("a", "Alpha", &[], &["b"]), node("b", "Beta", &["a"], &[]), node("c", "Gamma", &["b"], &[])]);
+        tree.insert("Away".to_string(), vec![node("x", "Xeno", &["a"], &[])]);
+        app.research = tree;
+        app.unlocked_nodes.clear();
+        app.revealed_nodes.clear();
+        app
+    }
+
+    fn missing(app: &Clicker, id: &str, prerequisites: &[&str]) -> String {
+        let missing = prerequisites.iter().map(|p| p.to_string()).collect();
+        app.unlock_message(id, Err(UnlockError::PrerequisitesMissing(missing))).unwrap_err()
+    }
+
+    #[test]
+    fn hidden_prerequisites_are_masked() {
+        let mut app = app();
+        assert_eq!(missing(&app, "c", &["b"]), "Can't research Gamma: research ??? first");
+        app.revealed_nodes.insert("b".to_string());
+        assert_eq!(missing(&app, "c", &["b"]), "Can't research Gamma: research Beta first");
+    }
+
+    #[test]
+    fn prerequisites_from_another_tab_name_it() {
+        let app = app();
+        assert_eq!(missing(&app, "x", &["a"]), "Can't research Xeno: research Alpha (Home) first");
+    }
+
+    #[test]
+    fn queued_message_lists_prerequisites_first() {
+        let app = app();
+        assert_eq!(app.queued_message(&["a".to_string()]), "Queued Alpha");
+        assert_eq!(app.queued_message(&["a".to_string(), "b".to_string(), "c".to_string()]), "Queued Gamma after Alpha, Beta");
+    }
+}
@@ -0,0 +1,293 @@
+// Timed research: a node with a `research_time` takes that long once its costs are
+// paid. Nodes wait in a queue, with any missing prerequisites queued ahead of them,
+// and the front one starts as soon as it can be afforded. Progress runs on the sim
+// clock, and the time since the last save is caught up when the game loads.
+use crate::alchemy::format_countdown;
+use crate::commands::GameCommand;
+use crate::research::NodeVisibility;
+use crate::{Clicker, UnlockError, UnlockOutcome};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResearchJob {
+    pub node: String,
+    pub remaining: f32,
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl Clicker {
+    pub fn research_time(&self, id: &str) -> f32 {
+        self.research_node(id).and_then(|n| n.research_time).unwrap_or(0.0)
+    }
+
+    pub fn queued_position(&self, id: &str) -> Option<usize> {
+        self.research_queue.iter().position(|q| q == id)
+    }
+
+    // Fraction of the running research done, if `id` is the one running
+    pub fn research_progress(&self, id: &str) -> Option<f32> {
+        let job = self.researching.as_ref().filter(|job| job.node == id)?;
+        let total = self.research_time(id).max(0.001);
+        Some((1.0 - job.remaining / total).clamp(0.0, 1.0))
+    }
+
+    // Queue `id` behind any prerequisites it still needs. Returns every node added,
+    // in the order they will be researched.
+    pub fn queue_research(&mut self, id: &str) -> Result<Vec<String>, UnlockError> {
+        if self.unlocked_nodes.contains(id) { return Err(UnlockError::AlreadyUnlocked); }
+        if self.researching.as_ref().is_some_and(|job| job.node == id) { return Err(UnlockError::InProgress); }
+        if self.research_queue.iter().any(|q| q == id) { return Err(UnlockError::AlreadyQueued); }
+        let visibility = self.research_visibility();
+        if visibility.get(id) != Some(&NodeVisibility::Visible) { return Err(UnlockError::NotFound); }
+
+        let (mut order, mut unknown) = (Vec::new(), Vec::new());
+        self.plan_research(id, &visibility, &mut HashSet::new(), &mut order, &mut unknown);
+        if !unknown.is_empty() { return Err(UnlockError::PrerequisitesMissing(unknown)); }
+
+        // Each node's tab must be open by the time it starts, possibly by an earlier one
+        let mut open: HashSet<String> = self.unlocked_research_tabs.clone();
+        let ahead = self.researching.iter().map(|job| &job.node).chain(&self.research_queue);
+        open.extend(ahead.filter_map(|q| self.research_node(q)?.unlocks_menu.clone()));
+        for node in &order {
+            let tab = self.node_tab(node).unwrap_or_default().to_string();
+            if !open.contains(&tab) { return Err(UnlockError::TabLocked(tab)); }
+            open.extend(self.research_node(node).and_then(|n| n.unlocks_menu.clone()));
+        }
+        self.research_queue.extend(order.iter().cloned());
+        Ok(order)
+    }
+
+    // Depth-first: prerequisites not yet researched or queued come before `id`.
+    // Prerequisites the player can't see yet end up in `unknown`.
+    fn plan_research(&self, id: &str, visibility: &HashMap<String, NodeVisibility>, seen: &mut HashSet<String>, order: &mut Vec<String>, unknown: &mut Vec<String>) {
+        if !seen.insert(id.to_string()) { return; }
+        if self.unlocked_nodes.contains(id) || self.research_queue.iter().any(|q| q == id) { return; }
+        if self.researching.as_ref().is_some_and(|job| job.node == id) { return; }
+        let Some(node) = self.research_node(id).filter(|_| visibility.get(id) == Some(&NodeVisibility::Visible)) else {
+            unknown.push(id.to_string());
+            return;
+        };
+        for pre in &node.prerequisites {
+            self.plan_research(pre, visibility, seen, order, unknown);
+        }
+        order.push(id.to_string());
+    }
+
+    // Take `id` out of the queue along with queued nodes that depend on it
+    pub fn dequeue_research(&mut self, id: &str) -> Vec<String> {
+        let mut removed = vec![id.to_string()];
+        for queued in self.research_queue.clone() {
+            let needs_removed = self.research_node(&queued).is_some_and(|n| n.prerequisites.iter().any(|p| removed.contains(p)));
+            if needs_removed && !removed.contains(&queued) { removed.push(queued); }
+        }
+        self.research_queue.retain(|q| !removed.contains(q));
+        removed
+    }
+
+    // Run the current research and start queued ones; leftover time carries over.
+    // Every completion, start and dropped job in the tick ends up in the message.
+    pub fn tick_research(&mut self, dt: f32) {
+        let mut dt = dt;
+        let mut messages: Vec<Result<String, String>> = Vec::new();
+        loop {
+            if let Some(job) = &mut self.researching {
+                if job.remaining > dt {
+                    job.remaining -= dt;
+                    break;
+                }
+                dt -= job.remaining;
+                let id = job.node.clone();
+                self.researching = None;
+                self.complete_research(&id);
+                messages.push(self.unlock_message(&id, Ok(UnlockOutcome::Unlocked)));
+            }
+            let Some(next) = self.research_queue.first().cloned() else { break; };
+            match self.unlock_node(&next) {
+                Ok(outcome) => {
+                    self.research_queue.remove(0);
+                    messages.push(self.unlock_message(&next, Ok(outcome)));
+                }
+                // The front waits until it can be paid for
+                Err(UnlockError::Insufficient(_)) => break,
+                // Anything else can't be fixed by waiting, so the job is dropped
+                Err(err) => {
+                    self.research_queue.remove(0);
+                    messages.push(self.unlock_message(&next, Err(err)));
+                }
+            }
+        }
+        if messages.is_empty() { return; }
+        let failed = messages.iter().any(Result::is_err);
+        let text = messages.into_iter().map(|m| m.unwrap_or_else(|e| e)).collect::<Vec<_>>().join("; ");
+        self.research_message = if failed { Err(text) } else { Ok(text) };
+    }
+
+    // Apply the research time that passed between saving and loading
+    pub fn catch_up_research(&mut self, saved_at: u64) {
+        let now = unix_now();
+        if saved_at == 0 || now <= saved_at { return; }
+        let before = self.unlocked_nodes.clone();
+        self.tick_research((now - saved_at) as f32);
+        let done: Vec<String> = self
+            .research
+            .values()
+            .flatten()
+            .filter(|n| self.unlocked_nodes.contains(&n.id) && !before.contains(&n.id))
+            .map(|n| n.name.clone())
+            .collect();
+        if done.is_empty() { return; }
+        let away = format!("While you were away: researched {}", done.join(", "));
+        // Keep any jobs dropped on the way
+        self.research_message = match &self.research_message {
+            Err(dropped) => Err(format!("{}; {}", away, dropped)),
+            Ok(_) => Ok(away),
+        };
+    }
+
+    // Sidebar section: the running research with its progress, then the queue
+    pub fn show_research_queue(&mut self, ui: &mut egui::Ui) {
+        if self.researching.is_none() && self.research_queue.is_empty() { return; }
+        ui.label(egui::RichText::new("Research").color(egui::Color32::WHITE));
+        if let Some(job) = self.researching.clone() {
+            let name = self.research_node(&job.node).map_or(job.node.clone(), |n| n.name.clone());
+            let progress = self.research_progress(&job.node).unwrap_or(0.0);
+            ui.label(egui::RichText::new(format!("{} ({} left)", name, format_countdown(job.remaining))).color(egui::Color32::LIGHT_BLUE));
+            ui.add(egui::ProgressBar::new(progress).desired_width(200.0));
+        }
+        let mut remove: Option<String> = None;
+        for (i, id) in self.research_queue.iter().enumerate() {
+            let Some(node) = self.research_node(id) else { continue; };
+            ui.horizontal(|ui| {
+                if ui.small_button("✖").on_hover_text("Remove from the queue").clicked() {
+                    remove = Some(id.clone());
+                }
+                let time = node.research_time.filter(|t| *t > 0.0).map(|t| format!(" ({})", format_countdown(t))).unwrap_or_default();
+                ui.label(egui::RichText::new(format!("{}. {}{}", i + 1, node.name, time)).color(egui::Color32::LIGHT_GRAY));
+            });
+            // Say what the next research is waiting for
+            if i == 0 && self.researching.is_none() {
+                let short: Vec<String> = self
+                    .cost_shortfalls(&node.cost)
+                    .iter()
+                    .map(|s| format!("{} {}", s.needed - s.have, self.aspect_name(&s.resource)))
+                    .collect();
+                if !short.is_empty() {
+                    ui.label(egui::RichText::new(format!("Waiting for {}", short.join(", "))).color(egui::Color32::from_rgb(240, 110, 100)));
+                }
+            }
+        }
+        if let Some(node) = remove { self.apply(GameCommand::DequeueResearch { node }); }
+        ui.separator();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save, ResearchNode, ResearchTree};
+    use indexmap::IndexMap;
+
+    fn node(id: &str, prerequisites: &[&str], vis: u32, time: f32) -> ResearchNode {
+        ResearchNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            x: Some(0.0),
+            y: Some(0.0),
+            cost: IndexMap::from([("Vis".to_string(), vis)]),
+            research_time: Some(time),
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks: None,
+            unlocks_nodes: None,
+            unlocks_menu: None,
+        }
+    }
+
+    // a -> b -> c in the open "Tab", 10 Vis each, taking 10s, 5s and 5s;
+    // x and y sit in "Closed", a tab never opened
+    fn app(vis: u32) -> Clicker {
+        let mut app = test_clicker(test_save(1));
+        let mut tree = ResearchTree::new();
+        tree.insert("Tab".to_string(), vec![node("a", &[], 10, 10.0), node("b", &["a"], 10, 5.0), node("c", &["b"], 10, 5.0)]);
+        tree.insert("Closed".to_string(), vec![node("x", &[], 0, 1.0), node("y", &[], 0, 1.0)]);
+        app.research = tree;
+        app.unlocked_research_tabs = HashSet::from(["Tab".to_string()]);
+        app.unlocked_nodes.clear();
+        app.vis = vis;
+        app
+    }
+
+    fn unlocked(app: &Clicker, ids: &[&str]) -> bool {
+        ids.iter().all(|id| app.unlocked_nodes.contains(*id))
+    }
+
+    #[test]
+    fn prerequisites_are_queued_first() {
+        let mut app = app(100);
+        assert_eq!(app.queue_research("c").unwrap(), vec!["a", "b", "c"]);
+        app.tick_research(0.0);
+        assert_eq!(app.researching.as_ref().map(|j| j.node.as_str()), Some("a"));
+        assert_eq!(app.research_queue, vec!["b", "c"]);
+        // Only the started job has been paid for
+        assert_eq!(app.vis, 90);
+        assert!(matches!(app.queue_research("b"), Err(UnlockError::AlreadyQueued)));
+    }
+
+    #[test]
+    fn leftover_time_carries_into_the_next_jobs() {
+        let mut app = app(100);
+        app.queue_research("c").unwrap();
+        app.tick_research(0.0);
+        app.tick_research(18.0);
+        assert!(unlocked(&app, &["a", "b"]));
+        let job = app.researching.as_ref().expect("c is running");
+        assert_eq!(job.node, "c");
+        assert!((job.remaining - 2.0).abs() < 1e-3, "remaining {}", job.remaining);
+        assert_eq!(app.vis, 70);
+    }
+
+    #[test]
+    fn unaffordable_front_job_waits() {
+        let mut app = app(15);
+        app.queue_research("c").unwrap();
+        app.tick_research(10.0);
+        assert!(unlocked(&app, &["a"]));
+        assert!(app.researching.is_none());
+        assert_eq!(app.research_queue, vec!["b", "c"]);
+
+        app.tick_research(100.0);
+        assert_eq!(app.research_queue, vec!["b", "c"]);
+
+        app.vis = 20;
+        app.tick_research(0.0);
+        assert_eq!(app.researching.as_ref().map(|j| j.node.as_str()), Some("b"));
+        assert_eq!(app.research_queue, vec!["c"]);
+    }
+
+    #[test]
+    fn offline_time_finishes_several_jobs() {
+        let mut app = app(100);
+        app.queue_research("c").unwrap();
+        app.tick_research(0.0);
+        app.catch_up_research(unix_now() - 60);
+        assert!(unlocked(&app, &["a", "b", "c"]));
+        assert!(app.researching.is_none() && app.research_queue.is_empty());
+        assert_eq!(app.research_message, Ok("While you were away: researched a, b, c".to_string()));
+    }
+
+    #[test]
+    fn every_dropped_job_is_reported() {
+        let mut app = app(100);
+        app.research_queue = vec!["x".to_string(), "y".to_string(), "a".to_string()];
+        app.tick_research(0.0);
+        assert_eq!(app.researching.as_ref().map(|j| j.node.as_str()), Some("a"));
+        let Err(message) = &app.research_message else { panic!("drops should be reported as errors") };
+        assert!(message.contains("research x") && message.contains("research y"), "{}", message);
+        assert!(message.contains("Started researching a"), "{}", message);
+    }
+}
@@ -0,0 +1,386 @@
+// Headless balance simulator: plays the game from a fresh save with a fixed
+// strategy and reports when each research node and crystal tier was reached.
+use crate::commands::{GameCommand, Upgrade};
+use crate::rng::RngState;
+use crate::{Clicker, GameData, ResearchNode, Savefile, DEFAULT_SAVE_JSON, SIM_STEP};
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+// Crafts attempted per simulation step, so a strategy can't loop forever
+const MAX_CRAFTS_PER_STEP: usize = 32;
+// Relative worth of one Vis when comparing research costs
+const VIS_WEIGHT: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    // Always work toward the cheapest reachable research node
+    Greedy,
+    // Only research what leads to this node, cheapest first
+    Target(String),
+    // Never research; just click
+    ClickOnly,
+}
+
+impl Strategy {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "greedy" => Strategy::Greedy,
+            "click-only" | "clicks" => Strategy::ClickOnly,
+            _ => match s.strip_prefix("target:") {
+                Some(node) if !node.is_empty() => Strategy::Target(node.to_string()),
+                _ => bail!("unknown strategy '{}' (expected greedy, target:<node> or click-only)", s),
+            },
+        })
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Strategy::Greedy => "greedy".to_string(),
+            Strategy::Target(node) => format!("target:{}", node),
+            Strategy::ClickOnly => "click-only".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub strategy: Strategy,
+    pub clicks_per_sec: f32,
+    // Simulated seconds before giving up
+    pub duration: f32,
+    pub seed: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TimelineEntry {
+    // Simulated seconds since start
+    pub time: f32,
+    // "research" or "tier"
+    pub kind: String,
+    pub id: String,
+    pub clicks: u32,
+    pub vis_earned: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SimSummary {
+    pub duration: f32,
+    pub finished: bool,
+    pub clicks: u32,
+    pub vis_earned: u32,
+    pub overflow_lost: u32,
+    pub overflow_captured: u32,
+    pub vis_per_minute: f32,
+    // Share of generated Vis that reached the pool (the rest overflowed)
+    pub vis_efficiency: f32,
+    pub max_vis: u32,
+    pub research_unlocked: usize,
+    pub research_total: usize,
+    pub flux: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SimReport {
+    pub strategy: String,
+    pub seed: u64,
+    pub clicks_per_sec: f32,
+    pub timeline: Vec<TimelineEntry>,
+    pub summary: SimSummary,
+}
+
+impl SimReport {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    // Timeline rows, then the summary as `summary` rows keyed by field
+    pub fn to_csv(&self) -> Result<String> {
+        let mut out = String::from("time,kind,id,clicks,vis_earned\n");
+        for e in &self.timeline {
+            out.push_str(&format!("{:.1},{},{},{},{}\n", e.time, e.kind, e.id, e.clicks, e.vis_earned));
+        }
+        let summary = serde_json::to_value(&self.summary)?;
+        if let Some(map) = summary.as_object() {
+            for (k, v) in map {
+                out.push_str(&format!("{:.1},summary,{},,{}\n", self.summary.duration, k, v));
+            }
+        }
+        Ok(out)
+    }
+}
+
+pub fn simulate(config: &SimConfig) -> Result<SimReport> {
+    let data = GameData::embedded()?;
+    if let Strategy::Target(node) = &config.strategy
+        && !data.research.values().flatten().any(|n| &n.id == node)
+    {
+        bail!("unknown research node '{}'", node);
+    }
+    let mut save: Savefile = serde_json::from_str(DEFAULT_SAVE_JSON)?;
+    save.rng = RngState { seed: Some(config.seed), word_pos: 0 };
+    let mut app = Clicker::from_save_with_data(save, data);
+    let mut sim = Simulation::new(&app, config.strategy.clone());
+
+    let steps = (config.duration / SIM_STEP).ceil() as u64;
+    let mut click_budget = 0.0f32;
+    let mut finished = false;
+    for _ in 0..steps {
+        app.step();
+        click_budget += config.clicks_per_sec * SIM_STEP;
+        while click_budget >= 1.0 {
+            click_budget -= 1.0;
+            app.apply(GameCommand::Gather);
+        }
+        if sim.strategy != Strategy::ClickOnly {
+            sim.act(&mut app);
+        }
+        sim.observe(&app);
+        if sim.done(&app) {
+            finished = true;
+            break;
+        }
+    }
+
+    let duration = app.sim_tick as f32 * SIM_STEP;
+    let generated = app.total_vis_earned + app.overflow_lost + app.overflow_captured;
+    let summary = SimSummary {
+        duration,
+        finished,
+        clicks: app.total_clicks,
+        vis_earned: app.total_vis_earned,
+        overflow_lost: app.overflow_lost,
+        overflow_captured: app.overflow_captured,
+        vis_per_minute: if duration > 0.0 { app.total_vis_earned as f32 * 60.0 / duration } else { 0.0 },
+        vis_efficiency: if generated > 0 { app.total_vis_earned as f32 / generated as f32 } else { 1.0 },
+        max_vis: app.maxVis,
+        research_unlocked: app.unlocked_nodes.len(),
+        research_total: app.research.values().map(|v| v.len()).sum(),
+        flux: app.flux,
+    };
+    Ok(SimReport {
+        strategy: config.strategy.label(),
+        seed: config.seed,
+        clicks_per_sec: config.clicks_per_sec,
+        timeline: sim.timeline,
+        summary,
+    })
+}
+
+struct Simulation {
+    strategy: Strategy,
+    // Nodes the strategy may research (None = all)
+    allowed: Option<HashSet<String>>,
+    // Rough worth of each aspect in primal crystals, for comparing costs
+    aspect_value: HashMap<String, u32>,
+    seen_nodes: HashSet<String>,
+    seen_tiers: HashSet<String>,
+    timeline: Vec<TimelineEntry>,
+}
+
+impl Simulation {
+    fn new(app: &Clicker, strategy: Strategy) -> Self {
+        let allowed = match &strategy {
+            Strategy::Target(node) => Some(ancestors(app, node)),
+            _ => None,
+        };
+        let mut aspect_value = HashMap::new();
+        for items in app.recipes.crystals.values() {
+            for name in items.keys() {
+                value_of(app, name, &mut aspect_value, &mut HashSet::new());
+            }
+        }
+        Self {
+            strategy,
+            allowed,
+            aspect_value,
+            seen_nodes: app.unlocked_nodes.clone(),
+            seen_tiers: HashSet::new(),
+            timeline: Vec::new(),
+        }
+    }
+
+    fn done(&self, app: &Clicker) -> bool {
+        match &self.strategy {
+            Strategy::Target(node) => app.unlocked_nodes.contains(node),
+            Strategy::Greedy => app.research.values().flatten().all(|n| app.unlocked_nodes.contains(&n.id)),
+            Strategy::ClickOnly => false,
+        }
+    }
+
+    fn cost_weight(&self, cost: &IndexMap<String, u32>) -> u32 {
+        cost.iter()
+            .map(|(k, amt)| match k.as_str() {
+                "Vis" => amt * VIS_WEIGHT,
+                _ => amt * self.aspect_value.get(k).copied().unwrap_or(1),
+            })
+            .sum()
+    }
+
+    // Cheapest node that is researchable now and whose costs can eventually be met.
+    // A target run detours outside the target's ancestors only when it has to,
+    // e.g. to unlock the crystal tier an ancestor costs.
+    fn goal<'a>(&self, app: &'a Clicker) -> Option<&'a ResearchNode> {
+        let candidates = || {
+            app.research
+                .values()
+                .flatten()
+                .filter(|n| app.can_unlock_node(&n.id))
+                .filter(|n| n.cost.keys().all(|k| obtainable(app, k, &mut HashSet::new())))
+        };
+        let allowed = self.allowed.as_ref();
+        candidates()
+            .filter(|n| allowed.is_none_or(|a| a.contains(&n.id)))
+            .min_by_key(|n| self.cost_weight(&n.cost))
+            .or_else(|| candidates().min_by_key(|n| self.cost_weight(&n.cost)))
+    }
+
+    fn act(&mut self, app: &mut Clicker) {
+        let Some(goal) = self.goal(app) else { return; };
+        let id = goal.id.clone();
+        let cost = goal.cost.clone();
+        if app.can_afford_cost(&cost) {
+            app.apply(GameCommand::Unlock { node: id });
+            return;
+        }
+        // Grow the Vis cap until it can hold the research cost
+        let vis_needed = cost.get("Vis").copied().unwrap_or(0);
+        if vis_needed > app.effective_max_vis() && app.upgrade_available(Upgrade::VisCapacity) {
+            app.apply(GameCommand::BuyUpgrade { upgrade: Upgrade::VisCapacity });
+        }
+        for _ in 0..MAX_CRAFTS_PER_STEP {
+            let missing: Vec<String> = cost
+                .iter()
+                .filter(|(k, amt)| k.as_str() != "Vis" && app.crystals.get(k.as_str()).copied().unwrap_or(0) < **amt)
+                .map(|(k, _)| k.clone())
+                .collect();
+            let crafted = missing.iter().any(|aspect| craft_toward(app, aspect, &cost, &mut HashSet::new()));
+            if !crafted { break; }
+        }
+    }
+
+    fn observe(&mut self, app: &Clicker) {
+        let time = app.sim_tick as f32 * SIM_STEP;
+        let mut new_nodes: Vec<&String> = app.unlocked_nodes.difference(&self.seen_nodes).collect();
+        new_nodes.sort();
+        let mut entries: Vec<TimelineEntry> = new_nodes
+            .into_iter()
+            .map(|id| TimelineEntry { time, kind: "research".to_string(), id: id.clone(), clicks: app.total_clicks, vis_earned: app.total_vis_earned })
+            .collect();
+        for (category, items) in &app.recipes.crystals {
+            if self.seen_tiers.contains(category) { continue; }
+            if items.keys().any(|name| app.crystals.get(name).copied().unwrap_or(0) > 0) {
+                self.seen_tiers.insert(category.clone());
+                entries.push(TimelineEntry { time, kind: "tier".to_string(), id: category.clone(), clicks: app.total_clicks, vis_earned: app.total_vis_earned });
+            }
+        }
+        for e in &entries {
+            if e.kind == "research" { self.seen_nodes.insert(e.id.clone()); }
+        }
+        self.timeline.extend(entries);
+    }
+}
+
+// `node` plus every node it transitively requires
+fn ancestors(app: &Clicker, node: &str) -> HashSet<String> {
+    let mut out = HashSet::new();
+    let mut stack = vec![node.to_string()];
+    while let Some(id) = stack.pop() {
+        if !out.insert(id.clone()) { continue; }
+        if let Some(n) = app.research.values().flatten().find(|n| n.id == id) {
+            stack.extend(n.prerequisites.iter().cloned());
+        }
+    }
+    out
+}
+
+fn value_of(app: &Clicker, aspect: &str, memo: &mut HashMap<String, u32>, visiting: &mut HashSet<String>) -> u32 {
+    if let Some(v) = memo.get(aspect) { return *v; }
+    let Some(costs) = app.recipe_for(aspect) else { return 1; };
+    if !visiting.insert(aspect.to_string()) { return 1; }
+    let costs = costs.clone();
+    let v = costs.iter().map(|(k, amt)| amt * value_of(app, k, memo, visiting)).sum::<u32>().max(1);
+    visiting.remove(aspect);
+    memo.insert(aspect.to_string(), v);
+    v
+}
+
+fn recipe_category<'a>(app: &'a Clicker, aspect: &str) -> Option<&'a String> {
+    app.recipes.crystals.iter().find(|(_, items)| items.contains_key(aspect)).map(|(cat, _)| cat)
+}
+
+// Primals come from gathering; everything else needs an unlocked, non-circular recipe
+fn obtainable(app: &Clicker, aspect: &str, visiting: &mut HashSet<String>) -> bool {
+    if aspect == "Vis" || aspect == "Soul" || aspect == "Souls" { return true; }
+    let Some(category) = recipe_category(app, aspect) else { return true; };
+    if !app.category_unlocked(category) || !visiting.insert(aspect.to_string()) { return false; }
+    let ok = app.recipe_for(aspect).is_some_and(|costs| {
+        costs.keys().filter(|k| k.as_str() != aspect).all(|k| obtainable(app, k, &mut visiting.clone()))
+    }) && !app.recipe_is_cyclic(aspect);
+    visiting.remove(aspect);
+    ok
+}
+
+// Craft one step toward `aspect`, never dipping into what `reserve` still needs.
+// Returns true if anything was crafted.
+fn craft_toward(app: &mut Clicker, aspect: &str, reserve: &IndexMap<String, u32>, visiting: &mut HashSet<String>) -> bool {
+    let Some(category) = recipe_category(app, aspect).cloned() else { return false; };
+    if !app.category_unlocked(&category) || app.recipe_is_cyclic(aspect) { return false; }
+    if !visiting.insert(aspect.to_string()) { return false; }
+    let Some(costs) = app.recipe_for(aspect).cloned() else { return false; };
+    let have = |app: &Clicker, k: &str| app.crystals.get(k).copied().unwrap_or(0);
+    let spare = |app: &Clicker, k: &str| have(app, k).saturating_sub(reserve.get(k).copied().unwrap_or(0));
+    if costs.iter().all(|(k, amt)| spare(app, k) >= *amt) {
+        app.apply(GameCommand::Craft { category, name: aspect.to_string() });
+        return true;
+    }
+    let short: Vec<String> = costs.iter().filter(|(k, amt)| spare(app, k) < **amt).map(|(k, _)| k.clone()).collect();
+    short.iter().any(|k| craft_toward(app, k, reserve, visiting))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(strategy: Strategy, seed: u64) -> SimConfig {
+        SimConfig { strategy, clicks_per_sec: 5.0, duration: 120.0, seed }
+    }
+
+    #[test]
+    fn strategy_parse_accepts_known_names() {
+        assert_eq!(Strategy::parse("greedy").unwrap(), Strategy::Greedy);
+        assert_eq!(Strategy::parse("click-only").unwrap(), Strategy::ClickOnly);
+        assert_eq!(Strategy::parse("clicks").unwrap(), Strategy::ClickOnly);
+        assert_eq!(Strategy::parse("target:alchemy").unwrap(), Strategy::Target("alchemy".to_string()));
+        assert!(Strategy::parse("target:").is_err());
+        assert!(Strategy::parse("lazy").is_err());
+    }
+
+    #[test]
+    fn to_csv_lists_timeline_then_summary() {
+        let report = simulate(&config(Strategy::ClickOnly, 1)).unwrap();
+        let csv = report.to_csv().unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("time,kind,id,clicks,vis_earned"));
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(rows.len(), report.timeline.len() + 12);
+        assert!(rows.iter().all(|r| r.split(',').count() == 5));
+        assert!(rows.contains(&format!("120.0,summary,clicks,,{}", report.summary.clicks).as_str()));
+    }
+
+    #[test]
+    fn seeded_run_is_deterministic() {
+        let a = simulate(&config(Strategy::Greedy, 42)).unwrap();
+        let b = simulate(&config(Strategy::Greedy, 42)).unwrap();
+        assert_eq!(a.to_json().unwrap(), b.to_json().unwrap());
+        assert_eq!(a.summary.clicks, 600);
+        assert!(!a.summary.finished);
+        assert!(a.summary.research_unlocked > 0);
+    }
+
+    #[test]
+    fn unknown_target_is_rejected() {
+        assert!(simulate(&config(Strategy::Target("nope".to_string()), 0)).is_err());
+    }
+}
@@ -0,0 +1,426 @@
+// Terminal frontend: the same game state and save file as the egui app, drawn
+// with ratatui so the game can be played over SSH or on a headless box.
+use crate::alchemy::format_countdown;
+use crate::commands::{GameCommand, Upgrade};
+use crate::research::NodeVisibility;
+use crate::{save_game, Clicker, OverflowMode, ResearchNode, COAGULATOR_VIS_PER_POTENTIA};
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Tabs};
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// How often the screen redraws when no key is pressed
+const FRAME_TIME: Duration = Duration::from_millis(100);
+const AUTOSAVE_SECS: f32 = 60.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Gather,
+    Craft,
+    Research,
+    Upgrades,
+    Settings,
+}
+
+const TABS: [(Tab, &str); 5] = [
+    (Tab::Gather, "Gather"),
+    (Tab::Craft, "Craft"),
+    (Tab::Research, "Research"),
+    (Tab::Upgrades, "Upgrades"),
+    (Tab::Settings, "Settings"),
+];
+
+// What Enter does on a row
+#[derive(Clone)]
+enum Action {
+    Command(GameCommand),
+    CycleOverflowMode,
+    ToggleMod(String, bool),
+    Save,
+    SaveAndQuit,
+}
+
+struct Row {
+    line: Line<'static>,
+    action: Option<Action>,
+}
+
+impl Row {
+    fn text(line: impl Into<Line<'static>>) -> Self {
+        Self { line: line.into(), action: None }
+    }
+
+    fn heading(text: String) -> Self {
+        Self::text(Line::from(text).bold().fg(Color::LightBlue))
+    }
+
+    // An actionable row, dimmed when it can't be used right now
+    fn action(text: String, enabled: bool, action: Action) -> Self {
+        let style = if enabled { Style::new() } else { Style::new().fg(Color::DarkGray) };
+        Self { line: Line::styled(text, style), action: Some(action) }
+    }
+}
+
+struct Terminal {
+    tab: usize,
+    // Selected row per tab
+    selected: [ListState; TABS.len()],
+    message: String,
+    // Last flux event moved into `message`, drawn in magenta while it's still showing
+    flux_notice: String,
+    quit: bool,
+}
+
+pub fn run(mut app: Clicker) -> Result<()> {
+    let mut state = Terminal {
+        tab: 0,
+        selected: Default::default(),
+        message: "g: gather  ←/→: tabs  ↑/↓: select  Enter: use  s: save  q: save and quit".to_string(),
+        flux_notice: String::new(),
+        quit: false,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = main_loop(&mut terminal, &mut app, &mut state);
+    ratatui::restore();
+
+    save_game(&app)?;
+    app.flush_recording();
+    result
+}
+
+fn main_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut Clicker, state: &mut Terminal) -> Result<()> {
+    let mut last = Instant::now();
+    while !state.quit {
+        let now = Instant::now();
+        let dt = now.duration_since(last).as_secs_f32();
+        last = now;
+        app.advance(dt);
+        app.poll_dev_reload(dt);
+        // A flux event is shown once; any later message replaces it
+        let flux = std::mem::take(&mut app.flux_message);
+        if !flux.is_empty() {
+            state.message = flux.clone();
+            state.flux_notice = flux;
+        }
+        app.autosave_timer += dt;
+        if app.autosave_timer >= AUTOSAVE_SECS {
+            let _ = save_game(app);
+            app.flush_recording();
+            app.autosave_timer = 0.0;
+        }
+
+        let rows = rows(app, TABS[state.tab].0);
+        terminal.draw(|f| draw(f, app, state, &rows))?;
+
+        if !event::poll(FRAME_TIME)? { continue; }
+        let Event::Key(key) = event::read()? else { continue; };
+        if key.kind != KeyEventKind::Press { continue; }
+        let list = &mut state.selected[state.tab];
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => state.quit = true,
+            KeyCode::Char('g') | KeyCode::Char(' ') => app.apply(GameCommand::Gather),
+            KeyCode::Char('s') => state.message = saved_message(app),
+            KeyCode::Left | KeyCode::BackTab => state.tab = (state.tab + TABS.len() - 1) % TABS.len(),
+            KeyCode::Right | KeyCode::Tab => state.tab = (state.tab + 1) % TABS.len(),
+            KeyCode::Up => list.select(Some(list.selected().unwrap_or(0).saturating_sub(1))),
+            KeyCode::Down => list.select(Some((list.selected().map_or(0, |i| i + 1)).min(rows.len().saturating_sub(1)))),
+            KeyCode::Enter => {
+                let action = list.selected().and_then(|i| rows.get(i)).and_then(|r| r.action.clone());
+                if let Some(action) = action { perform(app, state, action); }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn saved_message(app: &mut Clicker) -> String {
+    app.flush_recording();
+    match save_game(app) {
+        Ok(()) => "Game saved.".to_string(),
+        Err(e) => format!("Save failed: {}", e),
+    }
+}
+
+fn perform(app: &mut Clicker, state: &mut Terminal, action: Action) {
+    match action {
+        Action::Command(cmd @ (GameCommand::QueueResearch { .. } | GameCommand::DequeueResearch { .. })) => {
+            app.apply(cmd);
+            state.message = match &app.research_message {
+                Ok(text) | Err(text) => format!("{}.", text),
+            };
+        }
+        Action::Command(cmd) => app.apply(cmd),
+        Action::CycleOverflowMode => {
+            let mode = match app.overflow_mode {
+                OverflowMode::Discard => OverflowMode::Buffer,
+                OverflowMode::Buffer => OverflowMode::Potentia,
+                OverflowMode::Potentia => OverflowMode::Discard,
+            };
+            app.apply(GameCommand::SetOverflowMode { mode });
+        }
+        Action::ToggleMod(id, enabled) => {
+            if let Err(e) = app.change_mod(&id, Some(enabled), 0) {
+                state.message = format!("{:#}", e);
+            }
+        }
+        Action::Save => state.message = saved_message(app),
+        Action::SaveAndQuit => state.quit = true,
+    }
+}
+
+// The registry colour of an aspect as a terminal colour
+fn aspect_color(app: &Clicker, id: &str) -> Color {
+    let c = app.aspect_color(id);
+    Color::Rgb(c.r(), c.g(), c.b())
+}
+
+fn rows(app: &Clicker, tab: Tab) -> Vec<Row> {
+    let mut rows = Vec::new();
+    match tab {
+        Tab::Gather => {
+            rows.push(Row::action(format!("Conjure resources (+{} Vis)", app.vis_per_click()), true, Action::Command(GameCommand::Gather)));
+            if app.unlocks.vis_coagulator {
+                let mode = match app.overflow_mode {
+                    OverflowMode::Discard => "Discard".to_string(),
+                    OverflowMode::Buffer => "Buffer".to_string(),
+                    OverflowMode::Potentia => format!("Potentia ({} Vis each)", COAGULATOR_VIS_PER_POTENTIA),
+                };
+                rows.push(Row::action(format!("Overflow: {}", mode), true, Action::CycleOverflowMode));
+                rows.push(Row::action(
+                    format!("Release buffer ({}/{})", app.vis_buffer, app.maxVis),
+                    app.vis_buffer > 0 && app.vis < app.effective_max_vis(),
+                    Action::Command(GameCommand::ReleaseVisBuffer),
+                ));
+            }
+            rows.push(Row::text(Line::from(format!("Overflow captured: {}  lost: {}", app.overflow_captured, app.overflow_lost)).fg(Color::Gray)));
+            if app.unlocks.transmutation {
+                rows.push(Row::heading(format!("transmutation ({}% returned)", app.transmute_rate())));
+                for (item, amount) in &app.crystals {
+                    if *amount == 0 { continue; }
+                    let Ok(yields) = app.transmute_yield(item) else { continue; };
+                    let parts: Vec<String> = yields.iter().map(|(k, amt)| format!("{} x{}", app.aspect_name(k), amt)).collect();
+                    rows.push(Row::action(format!("{} -> {}", app.aspect_name(item), parts.join(", ")), true, Action::Command(GameCommand::Transmute { item: item.clone() })));
+                }
+            }
+        }
+        Tab::Craft => {
+            for (category, items) in &app.recipes.crystals {
+                if !app.category_unlocked(category) { continue; }
+                rows.push(Row::heading(format!("{} crystals ({}% success)", category, app.craft_chance(category))));
+                for (name, costs) in items {
+                    rows.push(Row::action(
+                        format!("{:<14} {}", app.aspect_name(name), app.cost_text(costs)),
+                        app.can_afford_cost(costs),
+                        Action::Command(GameCommand::Craft { category: category.clone(), name: name.clone() }),
+                    ));
+                }
+            }
+            if rows.is_empty() {
+                rows.push(Row::text("No crystal recipes researched yet."));
+            }
+            if !app.craft_message.is_empty() {
+                rows.push(Row::text(Line::from(app.craft_message.clone()).fg(Color::Gray)));
+            }
+        }
+        Tab::Research => {
+            let visibility = app.research_visibility();
+            if !app.research_queue.is_empty() {
+                rows.push(Row::heading("Queue (Enter removes)".to_string()));
+                for (i, id) in app.research_queue.iter().enumerate() {
+                    let name = app.research_node(id).map_or(id.clone(), |n| n.name.clone());
+                    rows.push(Row::action(format!("{}. {}", i + 1, name), true, Action::Command(GameCommand::DequeueResearch { node: id.clone() })));
+                }
+            }
+            for (tab, nodes) in &app.research {
+                if !app.unlocked_research_tabs.contains(tab) { continue; }
+                rows.push(Row::heading(tab.clone()));
+                for node in nodes {
+                    match visibility.get(&node.id) {
+                        Some(NodeVisibility::Visible) => {}
+                        Some(NodeVisibility::Placeholder) => {
+                            rows.push(Row::text(Line::from("[?] ???").fg(Color::DarkGray)));
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                    let unlocked = app.unlocked_nodes.contains(&node.id);
+                    let mark = if unlocked { "[x]" } else if app.can_unlock_node(&node.id) { "[ ]" } else { "[-]" };
+                    let mut text = format!("{} {}", mark, node.name);
+                    if !unlocked {
+                        text.push_str(&format!("  ({})", app.cost_text(&node.cost)));
+                        if let Some(time) = node.research_time.filter(|t| *t > 0.0) {
+                            text.push_str(&format!("  {}", format_countdown(time)));
+                        }
+                    }
+                    if let Some(progress) = app.research_progress(&node.id) {
+                        text.push_str(&format!("  researching {:.0}%", progress * 100.0));
+                    } else if let Some(i) = app.queued_position(&node.id) {
+                        text.push_str(&format!("  queued #{}", i + 1));
+                    }
+                    let available = !unlocked && app.can_unlock_node(&node.id) && app.can_afford_cost(&node.cost);
+                    let mut row = Row::action(text, available, Action::Command(GameCommand::QueueResearch { node: node.id.clone() }));
+                    if unlocked {
+                        row.line = row.line.fg(Color::Green);
+                    }
+                    rows.push(row);
+                    if !unlocked && !node.prerequisites.is_empty() {
+                        rows.push(Row::text(needs_line(app, tab, node, &visibility)));
+                    }
+                }
+            }
+        }
+        Tab::Upgrades => {
+            for upgrade in Upgrade::ALL {
+                rows.push(Row::action(app.upgrade_label(upgrade), app.upgrade_available(upgrade), Action::Command(GameCommand::BuyUpgrade { upgrade })));
+            }
+        }
+        Tab::Settings => {
+            rows.push(Row::text(Line::from(format!("RNG seed: {}", app.rng.seed())).fg(Color::Gray)));
+            rows.push(Row::action("Save Game".to_string(), true, Action::Save));
+            rows.push(Row::action("Save and Exit".to_string(), true, Action::SaveAndQuit));
+            rows.push(Row::heading("Mods".to_string()));
+            for m in &app.mods.mods {
+                let mark = if m.enabled { "[x]" } else { "[ ]" };
+                rows.push(Row::action(format!("{} {} {}", mark, m.name, m.version), true, Action::ToggleMod(m.id.clone(), !m.enabled)));
+            }
+            for c in &app.mods.conflicts {
+                rows.push(Row::text(Line::from(c.clone()).fg(Color::Yellow)));
+            }
+            for e in &app.mods.errors {
+                rows.push(Row::text(Line::from(e.clone()).fg(Color::LightRed)));
+            }
+            if let Some(rec) = &app.recorder {
+                rows.push(Row::text(
+                    Line::from(format!("Recording {} commands to {}", rec.recording.commands.len(), rec.path.display())).fg(Color::LightRed),
+                ));
+            }
+        }
+    }
+    rows
+}
+
+// "needs: A, B (Other tab)" under a research row, green once each prerequisite is
+// done; prerequisites still in the fog show as ???
+fn needs_line(app: &Clicker, tab: &str, node: &ResearchNode, visibility: &HashMap<String, NodeVisibility>) -> Line<'static> {
+    let mut spans = vec![Span::raw("      needs: ")];
+    for (i, pre) in node.prerequisites.iter().enumerate() {
+        if i > 0 { spans.push(Span::raw(", ")); }
+        let name = app.visible_node_label(pre, Some(tab), visibility);
+        let color = if app.unlocked_nodes.contains(pre) { Color::Green } else { Color::Red };
+        spans.push(Span::styled(name, Style::new().fg(color)));
+    }
+    Line::from(spans)
+}
+
+fn draw(f: &mut Frame, app: &Clicker, state: &mut Terminal, rows: &[Row]) {
+    let [tabs_area, body, status_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)]).areas(f.area());
+    let [main_area, inventory_area] = Layout::horizontal([Constraint::Min(0), Constraint::Length(32)]).areas(body);
+
+    let titles: Vec<&str> = TABS.iter().map(|(_, t)| *t).collect();
+    f.render_widget(
+        Tabs::new(titles)
+            .select(state.tab)
+            .highlight_style(Style::new().bold().fg(Color::Yellow))
+            .block(Block::bordered().title(" Clicker Game ")),
+        tabs_area,
+    );
+
+    let items: Vec<ListItem> = rows.iter().map(|r| ListItem::new(r.line.clone())).collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(format!(" {} ", TABS[state.tab].1)))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(list, main_area, &mut state.selected[state.tab]);
+
+    draw_inventory(f, app, inventory_area);
+
+    let mut status = vec![Line::from(state.message.clone())];
+    if state.message == state.flux_notice {
+        status[0] = status[0].clone().fg(Color::Magenta);
+    }
+    if let Some(err) = app.dev_errors.first().filter(|_| app.dev.is_some()) {
+        status[0] = Line::from(format!("{} ({} data problem(s))", err, app.dev_errors.len())).fg(Color::LightRed);
+    }
+    f.render_widget(Paragraph::new(status).block(Block::bordered()), status_area);
+}
+
+fn draw_inventory(f: &mut Frame, app: &Clicker, area: Rect) {
+    let mut lines = vec![
+        Line::from(format!("Vis: {}/{}", app.vis, app.effective_max_vis())).bold(),
+        Line::from(format!("Flux: {:.0}/{:.0}", app.flux, crate::flux::FLUX_MAX)).fg(Color::Magenta),
+    ];
+    if app.unlocks.vis_coagulator {
+        lines.push(Line::from(format!("Buffer: {}", app.vis_buffer)));
+    }
+    if let Some(job) = &app.researching {
+        let name = app.research_node(&job.node).map_or(job.node.clone(), |n| n.name.clone());
+        lines.push(Line::from(format!("Researching {}", name)).fg(Color::LightBlue));
+        lines.push(Line::from(format!("  {:.0}%, {} left", app.research_progress(&job.node).unwrap_or(0.0) * 100.0, format_countdown(job.remaining))).fg(Color::LightBlue));
+    }
+    if !app.research_queue.is_empty() {
+        lines.push(Line::from(format!("Queued research: {}", app.research_queue.len())).fg(Color::Gray));
+    }
+    lines.push(Line::from(""));
+    for (crystal, amount) in app.crystals.iter().filter(|(k, _)| app.aspect_discovered(k)) {
+        if *amount == 0 { continue; }
+        lines.push(Line::from(format!("{:<14}{:>6}", app.aspect_name(crystal), amount)).fg(aspect_color(app, crystal)));
+    }
+    f.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Inventory ")), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_clicker, test_save, ResearchTree};
+    use indexmap::IndexMap;
+
+    fn node(id: &str, prerequisites: &[&str], reveals: &[&str]) -> ResearchNode {
+        ResearchNode {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            description: String::new(),
+            x: Some(0.0),
+            y: Some(0.0),
+            cost: IndexMap::new(),
+            research_time: None,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            unlocks: None,
+            unlocks_nodes: Some(reveals.iter().map(|r| r.to_string()).collect()),
+            unlocks_menu: None,
+        }
+    }
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn needs_line_masks_hidden_prerequisites() {
+        let mut app = test_clicker(test_save(1));
+        let mut tree = ResearchTree::new();
+        // a reveals b; c needs both, x in another tab needs a
+        tree.insert("Home".to_string(), vec![node("a", &[], &["b"]), node("b", &[], &[]), node("c", &["a", "b"], &[])]);
+        tree.insert("Away".to_string(), vec![node("x", &["a"], &[])]);
+        app.research = tree;
+        app.unlocked_nodes.clear();
+        app.revealed_nodes.clear();
+
+        let line = |app: &Clicker, tab: &str, id: &str| {
+            let node = app.research_node(id).unwrap().clone();
+            text(&needs_line(app, tab, &node, &app.research_visibility()))
+        };
+        assert_eq!(line(&app, "Home", "c"), "      needs: A, ???");
+        assert_eq!(line(&app, "Away", "x"), "      needs: A (Home)");
+        app.unlocked_nodes.insert("a".to_string());
+        app.reveal_researched_nodes();
+        assert_eq!(line(&app, "Home", "c"), "      needs: A, B");
+    }
+}